@@ -0,0 +1,87 @@
+use std::time::Instant;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use iced_native::{layout, renderer, Layout, Length, Point, Rectangle, Size, Widget};
+use iced_native::Renderer as _;
+
+use ganic_no_std::{NUM_PERCS, NUM_STEPS};
+use iced_sequencing::core::grid::{GridEvent, GridPattern};
+use iced_sequencing::graphics::headless;
+use iced_sequencing::native::grid;
+
+const SIZE: Size = Size::new(1200., 400.);
+
+// every other step on every track
+fn get_pattern() -> GridPattern {
+    let mut pattern = GridPattern::new();
+
+    for step in (0..NUM_STEPS).step_by(2) {
+        for track in 0..NUM_PERCS {
+            pattern.data.insert(
+                (step, track),
+                GridEvent {
+                    offset: 0.,
+                    velocity: (step + 1) as f32 / NUM_STEPS as f32,
+                    selected: false,
+                },
+            );
+        }
+    }
+
+    pattern
+}
+
+fn draw_frame(renderer: &mut headless::Renderer, state: &mut grid::State, pattern: &GridPattern) {
+    let grid: grid::Grid<(), headless::Renderer> = grid::Grid::new(
+        state,
+        pattern.clone(),
+        |_| (),
+        Length::Fill,
+        Length::Fill,
+    );
+
+    let node = grid.layout(renderer, &layout::Limits::new(Size::ZERO, SIZE));
+
+    renderer.clear();
+    grid.draw(
+        renderer,
+        &renderer::Style::default(),
+        Layout::new(&node),
+        Point::ORIGIN,
+        &Rectangle::with_size(SIZE),
+    );
+}
+
+fn playback(c: &mut Criterion) {
+    let mut renderer = headless::renderer();
+    let pattern = get_pattern();
+    let mut state = grid::State::new(pattern.clone());
+    state.is_playing(true);
+
+    // fill the static caches once, like the first frame would
+    draw_frame(&mut renderer, &mut state, &pattern);
+
+    let mut step = 0;
+
+    c.bench_function("grid frame during playback", |b| {
+        b.iter(|| {
+            step = (step + 1) % NUM_STEPS;
+            state.transport_at([Some(step); NUM_PERCS], Instant::now());
+            state.set_playhead(Some(step as f32 + 0.5));
+
+            draw_frame(&mut renderer, &mut state, &pattern);
+        })
+    });
+
+    c.bench_function("grid frame with an edited pattern", |b| {
+        b.iter(|| {
+            // what every playback frame used to cost
+            state.set_pattern(pattern.clone());
+
+            draw_frame(&mut renderer, &mut state, &pattern);
+        })
+    });
+}
+
+criterion_group!(benches, playback);
+criterion_main!(benches);
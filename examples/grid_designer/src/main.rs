@@ -1,17 +1,15 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 // Import iced modules.
 use iced::{
     Color, Column, Container, scrollable, Scrollable,
-    Element, Length, Sandbox, Settings, Text,
-    container, Alignment, TextInput, text_input, Row
+    Element, Length, Application, Settings, Text, Command, Subscription,
+    container, Alignment, TextInput, text_input, Row, executor, time
 };
 
 use iced_sequencing::grid::{self, GridColor};
-use iced_sequencing::style::grid::{
-    Style, StyleSheet,
-    Grid, Event, Stroke
-};
+use iced_sequencing::style::theme::{GridColorOverride, GridTheme, Theme, ThemeWatcher};
 use iced_sequencing::style::color_utils::*;
 use iced_sequencing::core::grid::{
     GridPattern,
@@ -37,9 +35,13 @@ impl container::StyleSheet for MainContainerStyle {
 #[derive(Debug, Clone)]
 pub enum Message {
     GridEvent(GridMessage),
-    SetColor(ColorTarget, String)
+    SetColor(ColorTarget, String),
+    PollTheme(Instant)
 }
 
+// edit it while the example runs, the grid is restyled on save
+const THEME_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/theme.toml");
+
 
 pub fn main() {
     App::run(Settings {
@@ -51,16 +53,19 @@ pub fn main() {
 pub struct App {
     scroll: scrollable::State,
     hex_data: HashMap<ColorTarget, Input>,
-    style_sheet: CustomStyleSheet,
+    grid_theme: GridTheme,
+    theme_watcher: Option<ThemeWatcher>,
     grid_state: grid::State,
     live_pattern: GridPattern,
     focused_track: usize
 }
 
-impl<'a> Sandbox for App {
+impl<'a> Application for App {
+    type Executor = executor::Default;
     type Message = Message;
+    type Flags = ();
 
-    fn new() -> App  {
+    fn new(_flags: ()) -> (App, Command<Message>) {
         let initial_pattern = GridPattern::new();
         let mut h = HashMap::new();
 
@@ -68,30 +73,41 @@ impl<'a> Sandbox for App {
             h.insert(c, Input::new(c));
         });
 
-        App {
-            scroll: scrollable::State::new(),
-            hex_data: h,
-            style_sheet: CustomStyleSheet {
-                basic: Style {
-                    event: Event::default(),
-                    grid: Grid::default(),
-                    background: None,
-                    selection_stroke: Stroke { color: hex("#8ea5a8"), line_width: 0.7 },
-                    selected_track_bg_color: lighten(Color::BLACK, 0.7),
-                    current_step_bg_color: lighten(hex("374140"), 0.1)
-                }
+        let theme = Theme::load(THEME_PATH).unwrap_or_else(|error| {
+            println!("{}", error);
+            Theme::default()
+        });
+
+        let theme_watcher = ThemeWatcher::new(THEME_PATH)
+            .map_err(|error| println!("{}", error))
+            .ok();
+
+        (
+            App {
+                scroll: scrollable::State::new(),
+                hex_data: h,
+                grid_theme: theme.grid,
+                theme_watcher,
+                grid_state: grid::State::new(initial_pattern.clone()),
+                live_pattern: initial_pattern,
+                focused_track: 0
             },
-            grid_state: grid::State::new(initial_pattern.clone()),
-            live_pattern: initial_pattern,
-            focused_track: 0
-        }
+            Command::none()
+        )
     }
 
     fn title(&self) -> String {
         format!("Grid designer - Iced Sequencing")
     }
 
-    fn update(&mut self, event: Message) {
+    fn subscription(&self) -> Subscription<Message> {
+        match self.theme_watcher {
+            Some(_) => time::every(Duration::from_millis(500)).map(Message::PollTheme),
+            None => Subscription::none()
+        }
+    }
+
+    fn update(&mut self, event: Message) -> Command<Message> {
         // println!("--- update {:?}", event);
 
         match event {
@@ -112,14 +128,14 @@ impl<'a> Sandbox for App {
                             ColorTarget::EventBg => {
                                 println!("set color to EventBg in stylesheet");
 
-                                self.style_sheet.basic.event.bg_color = GridColor::Simple(iced_color);
+                                self.grid_theme.event.bg_color = Some(GridColorOverride(GridColor::Simple(iced_color)));
                             },
                             ColorTarget::EventContourBg => {},
                             ColorTarget::EventStroke => {},
                             ColorTarget::EventSliderBg => {
                                 println!("set color to EventSliderBg in stylesheet");
 
-                                self.style_sheet.basic.event.slider_bg_color = GridColor::Simple(iced_color);
+                                self.grid_theme.event.slider_bg_color = Some(GridColorOverride(GridColor::Simple(iced_color)));
                             },
                             ColorTarget::EventSliderHighlightedBg => {},
                             ColorTarget::EventNegativeOffsetMarkerBg => {},
@@ -142,8 +158,20 @@ impl<'a> Sandbox for App {
 
                 let input = self.hex_data.get_mut(&color_target).unwrap();
                 input.update(color);
+            },
+            Message::PollTheme(_) => {
+                // the colors typed in the inputs are replaced by the reloaded theme
+                match self.theme_watcher.as_ref().and_then(|watcher| watcher.poll()) {
+                    Some(Ok(theme)) => {
+                        self.grid_theme = theme.grid;
+                    },
+                    Some(Err(error)) => println!("{}", error),
+                    None => {}
+                }
             }
         }
+
+        Command::none()
     }
 
     fn view(&mut self) -> Element<Message> {
@@ -154,7 +182,7 @@ impl<'a> Sandbox for App {
                 Length::from(Length::Units(690)),
                 Length::from(Length::Units(345))
             )
-            .style(self.style_sheet.clone());
+            .style(self.grid_theme.clone());
 
         let inputs = self.hex_data.iter_mut().fold(
             Column::new(),
@@ -257,28 +285,6 @@ impl From<ColorTarget> for String {
     }
 }
 
-#[derive(Debug, Clone)]
-struct CustomStyleSheet {
-    pub basic: Style
-}
-
-impl StyleSheet for CustomStyleSheet {
-    fn default(&self) -> Style {
-        Style { 
-            event: self.basic.event.clone(), 
-            grid: self.basic.grid.clone(), 
-            background: self.basic.background, 
-            selection_stroke: self.basic.selection_stroke, 
-            selected_track_bg_color: self.basic.selected_track_bg_color, 
-            current_step_bg_color: self.basic.current_step_bg_color
-        }
-    }
-
-    fn dragging_selection(&self) -> Style {
-        self.default()
-    }
-}
-
 #[derive(Debug)]
 struct Input {
     target: ColorTarget,
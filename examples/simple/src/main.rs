@@ -187,7 +187,8 @@ impl Sandbox for App {
             Message::GridEvent,
             Length::from(Length::Units(690)),
             Length::from(Length::Units(345))
-        );
+        )
+        .focused_track(self.focused_track);
 
         let multi_slider = multi_slider::MultiSlider::new(
                 &mut self.multi_slider,
@@ -6,6 +6,18 @@ use crate::native::grid::State;
 pub const TRACK_MARGIN_BOTTOM: f32 = 2.0;
 pub const DEFAULT_VELOCITY: f32 = 1.0;
 pub const OFFSET_THRESHOLD: f32 = 0.05;
+pub const ZOOM_FACTOR: f32 = 1.25; // per wheel line
+pub const MAX_ZOOM: f32 = 8.0;
+pub const VELOCITY_HANDLE_HEIGHT: f32 = 6.0;
+const SNAP_EPSILON: f32 = 0.001;
+pub const SIMILAR_VELOCITY_RANGE: f32 = 0.1;
+pub const STEPS_PER_BEAT: usize = NUM_STEPS / 4; // a pattern is one 4/4 bar
+pub const BEATS: usize = NUM_STEPS / STEPS_PER_BEAT;
+
+// in milliseconds
+pub fn get_step_duration(bpm: f32) -> f32 {
+    60_000. / (bpm * STEPS_PER_BEAT as f32)
+}
 
 pub fn get_step_dimensions(size: Size) -> Size {
     return Size {
@@ -22,7 +34,8 @@ pub fn get_step_width(size: Size) -> f32 {
     return size.width / (NUM_STEPS + 2) as f32;
 }
 
-pub fn get_event_bounds(step: usize, track: usize, offset: f32, size: Size) -> Rectangle {
+// bounds of an event in content coordinates, the whole pattern being laid out on `size`
+pub fn get_event_content_bounds(step: usize, track: usize, offset: f32, size: Size) -> Rectangle {
     let step_width = get_step_width(size);
     let track_height = get_track_height(size);
 
@@ -34,7 +47,29 @@ pub fn get_event_bounds(step: usize, track: usize, offset: f32, size: Size) -> R
     }
 }
 
-pub fn get_hovered_step(cursor: Point, bounds: Rectangle, quantized: bool) -> (usize, usize, f32) {
+pub fn get_event_bounds(
+    step: usize,
+    track: usize,
+    offset: f32,
+    size: Size,
+    viewport: Viewport,
+) -> Rectangle {
+    let event_bounds =
+        get_event_content_bounds(step, track, offset, viewport.get_content_size(size));
+
+    Rectangle {
+        x: event_bounds.x - viewport.get_scroll_offset(size),
+        ..event_bounds
+    }
+}
+
+pub fn get_hovered_step(
+    cursor: Point,
+    bounds: Rectangle,
+    viewport: Viewport,
+    quantized: bool,
+) -> (usize, usize, f32) {
+    let bounds = viewport.get_content_bounds(bounds);
     let size = bounds.size();
     let step_width = get_step_width(size);
     let track_height = get_track_height(size);
@@ -68,6 +103,22 @@ pub fn get_hovered_step(cursor: Point, bounds: Rectangle, quantized: bool) -> (u
     (step, track, offset)
 }
 
+// x of a position in steps, offset included, so other widgets can line up with the grid
+pub fn get_step_position(position: f32, bounds: Rectangle, viewport: Viewport) -> f32 {
+    let content_bounds = viewport.get_content_bounds(bounds);
+
+    content_bounds.x + (position + 1.) * get_step_width(content_bounds.size())
+}
+
+pub fn get_hovered_position(cursor: Point, bounds: Rectangle, viewport: Viewport) -> f32 {
+    let content_bounds = viewport.get_content_bounds(bounds);
+    let step_width = get_step_width(content_bounds.size());
+
+    ((cursor.x - content_bounds.x - step_width) / step_width)
+        .max(0.)
+        .min(NUM_STEPS as f32)
+}
+
 pub fn get_hovered_track(cursor: Point, bounds: Rectangle) -> usize {
     (((cursor.y - bounds.y) / get_track_height(bounds.size())) as usize)
         .max(0)
@@ -90,6 +141,124 @@ pub fn convert_point_to_relative_coordinates(geometry: Rectangle, bounds: Rectan
     }
 }
 
+/// The horizontal zoom and scroll of a [`Grid`].
+///
+/// A zoom of 1 fits the whole pattern in the widget, the scroll is the number
+/// of steps hidden on the left.
+///
+/// [`Grid`]: crate::native::grid::Grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub zoom: f32,
+    pub scroll: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            zoom: 1.,
+            scroll: 0.,
+        }
+    }
+}
+
+impl Viewport {
+    // size of the whole pattern once zoomed
+    pub fn get_content_size(&self, size: Size) -> Size {
+        Size {
+            width: size.width * self.zoom,
+            ..size
+        }
+    }
+
+    pub fn get_scroll_offset(&self, size: Size) -> f32 {
+        self.scroll * get_step_width(self.get_content_size(size))
+    }
+
+    pub fn get_content_bounds(&self, bounds: Rectangle) -> Rectangle {
+        Rectangle {
+            x: bounds.x - self.get_scroll_offset(bounds.size()),
+            width: bounds.width * self.zoom,
+            ..bounds
+        }
+    }
+
+    // geometry is relative to the widget
+    pub fn convert_rectangle_to_content_coordinates(
+        &self,
+        geometry: Rectangle,
+        size: Size,
+    ) -> Rectangle {
+        Rectangle {
+            x: geometry.x + self.get_scroll_offset(size),
+            ..geometry
+        }
+    }
+
+    // anchor is the position of the cursor in the widget, from 0 to 1
+    // the step under it stays in place
+    pub fn zoom(&mut self, delta: f32, anchor: f32) {
+        let anchor_step = self.scroll + anchor * self.get_visible_steps();
+
+        self.zoom = (self.zoom * ZOOM_FACTOR.powf(delta)).max(1.).min(MAX_ZOOM);
+        self.scroll = anchor_step - anchor * self.get_visible_steps();
+        self.clamp_scroll();
+    }
+
+    pub fn scroll(&mut self, steps: f32) {
+        self.scroll += steps;
+        self.clamp_scroll();
+    }
+
+    fn get_visible_steps(&self) -> f32 {
+        (NUM_STEPS + 2) as f32 / self.zoom
+    }
+
+    fn clamp_scroll(&mut self) {
+        let max_scroll = (NUM_STEPS + 2) as f32 - self.get_visible_steps();
+        self.scroll = self.scroll.min(max_scroll).max(0.);
+    }
+}
+
+/// The positions quantized moves and keyboard nudges snap to, in fractions of a step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Snap {
+    Off,
+    Whole,
+    Half,
+    Third,
+    Quarter,
+    Custom(f32),
+}
+
+impl Default for Snap {
+    fn default() -> Self {
+        Snap::Whole
+    }
+}
+
+impl Snap {
+    pub fn get_size(&self) -> Option<f32> {
+        match self {
+            Snap::Off => None,
+            Snap::Whole => Some(1.),
+            Snap::Half => Some(1. / 2.),
+            Snap::Third => Some(1. / 3.),
+            Snap::Quarter => Some(1. / 4.),
+            Snap::Custom(size) if *size > 0. => Some(*size),
+            Snap::Custom(_) => None,
+        }
+    }
+
+    // position is in steps, offset included
+    pub fn snap(&self, position: f32) -> f32 {
+        match self.get_size() {
+            Some(size) => (position / size).round() * size,
+            None => position,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct GridEvent {
     pub offset: f32,
@@ -123,7 +292,14 @@ impl GridPattern {
         &self,
         cursor: Point,
         bounds: Rectangle,
+        viewport: Viewport,
     ) -> Option<(&(usize, usize), &GridEvent)> {
+        // events scrolled out of the widget can't be hovered
+        if !bounds.contains(cursor) {
+            return None;
+        }
+
+        let bounds = viewport.get_content_bounds(bounds);
         let size = bounds.size();
         let step_width = get_step_width(size);
         let track_height = get_track_height(size);
@@ -140,6 +316,43 @@ impl GridPattern {
         })
     }
 
+    // the top edge of the velocity bar of an event can be grabbed to set its velocity,
+    // the bar is drawn inside the event contour, `inset` wide
+    pub fn get_hovered_velocity_handle(
+        &self,
+        cursor: Point,
+        bounds: Rectangle,
+        viewport: Viewport,
+        inset: f32,
+    ) -> Option<(&(usize, usize), &GridEvent)> {
+        if !bounds.contains(cursor) {
+            return None;
+        }
+
+        self.data.iter().find(|((step, track), grid_event)| {
+            let event_bounds =
+                get_event_bounds(*step, *track, grid_event.offset, bounds.size(), viewport);
+            let bar_bounds = Rectangle {
+                x: bounds.x + event_bounds.x + inset,
+                y: bounds.y + event_bounds.y + inset,
+                width: (event_bounds.width - 2. * inset).max(0.),
+                height: (event_bounds.height - 2. * inset).max(0.),
+            };
+            let handle_y = bar_bounds.y + bar_bounds.height * (1. - grid_event.velocity);
+
+            // the handle never spills over the event edges, which grab the event
+            bar_bounds.contains(cursor) && (cursor.y - handle_y).abs() <= VELOCITY_HANDLE_HEIGHT / 2.
+        })
+    }
+
+    pub fn get_selection(&self) -> Vec<(usize, usize)> {
+        self.data
+            .iter()
+            .filter(|(_, grid_event)| grid_event.selected)
+            .map(|(grid_id, _)| *grid_id)
+            .collect()
+    }
+
     pub fn toggle_select(&mut self, grid_id: (usize, usize)) {
         match self.data.get_mut(&grid_id) {
             Some(grid_event) => {
@@ -159,15 +372,25 @@ impl GridPattern {
         }
     }
 
+    // area selections are in content coordinates, see Viewport::get_content_size
     pub fn add_selected_area(&mut self, selection: Rectangle, size: Size) {
         self.data
             .iter_mut()
             .for_each(|((step, track), grid_event)| {
-                let event_bounds = get_event_bounds(*step, *track, grid_event.offset, size);
+                let event_bounds = get_event_content_bounds(*step, *track, grid_event.offset, size);
                 if selection.intersection(&event_bounds).is_some() { grid_event.selected = true; }
             });
     }
 
+    pub fn remove_selected_area(&mut self, selection: Rectangle, size: Size) {
+        self.data
+            .iter_mut()
+            .for_each(|((step, track), grid_event)| {
+                let event_bounds = get_event_content_bounds(*step, *track, grid_event.offset, size);
+                if selection.intersection(&event_bounds).is_some() { grid_event.selected = false; }
+            });
+    }
+
     pub fn select_all(&mut self) {
         self.data.iter_mut().for_each(|(_, grid)| {
             grid.selected = true;
@@ -184,7 +407,7 @@ impl GridPattern {
         self.data
             .iter_mut()
             .for_each(|((step, track), grid_event)| {
-                let event_bounds = get_event_bounds(*step, *track, grid_event.offset, size);
+                let event_bounds = get_event_content_bounds(*step, *track, grid_event.offset, size);
 
                 match selection.intersection(&event_bounds) {
                     Some(_) => {
@@ -201,7 +424,7 @@ impl GridPattern {
         self.data
             .iter_mut()
             .for_each(|((step, track), grid_event)| {
-                let event_bounds = get_event_bounds(*step, *track, grid_event.offset, size);
+                let event_bounds = get_event_content_bounds(*step, *track, grid_event.offset, size);
 
                 match selection.intersection(&event_bounds) {
                     Some(_) => {
@@ -212,6 +435,62 @@ impl GridPattern {
             });
     }
 
+    pub fn select_track(&mut self, track: usize) {
+        self.data.iter_mut().for_each(|((_, event_track), grid)| {
+            grid.selected = *event_track == track;
+        });
+    }
+
+    pub fn select_column(&mut self, step: usize) {
+        self.data.iter_mut().for_each(|((event_step, _), grid)| {
+            grid.selected = *event_step == step;
+        });
+    }
+
+    // events on the tracks of the selection, with a velocity close to the selected ones
+    pub fn select_similar(&mut self) {
+        let mut velocity_ranges: HashMap<usize, (f32, f32)> = HashMap::new();
+
+        self.data
+            .iter()
+            .filter(|(_, grid_event)| grid_event.selected)
+            .for_each(|((_, track), grid_event)| {
+                let range = velocity_ranges
+                    .entry(*track)
+                    .or_insert((grid_event.velocity, grid_event.velocity));
+
+                range.0 = range.0.min(grid_event.velocity);
+                range.1 = range.1.max(grid_event.velocity);
+            });
+
+        self.data.iter_mut().for_each(|((_, track), grid_event)| {
+            grid_event.selected = match velocity_ranges.get(track) {
+                Some((min, max)) => {
+                    grid_event.velocity >= min - SIMILAR_VELOCITY_RANGE
+                        && grid_event.velocity <= max + SIMILAR_VELOCITY_RANGE
+                }
+                None => false,
+            };
+        });
+    }
+
+    pub fn invert_selection(&mut self) {
+        self.data.iter_mut().for_each(|(_, grid)| {
+            grid.selected = !grid.selected;
+        });
+    }
+
+    // every nth step starting from offset, e.g. (4, 2) selects steps 2, 6, 10...
+    pub fn select_every_nth_step(&mut self, n: usize, offset: usize) {
+        self.data.iter_mut().for_each(|((step, _), grid)| {
+            grid.selected = n > 0 && *step >= offset && (*step - offset) % n == 0;
+        });
+    }
+
+    pub fn clear_track(&mut self, track: usize) {
+        self.data.retain(|(_, event_track), _| *event_track != track);
+    }
+
     pub fn remove_selection(&mut self) {
         for ((step, track), event) in self.data.to_owned() {
             if event.selected {
@@ -226,57 +505,113 @@ impl GridPattern {
         });
     }
 
+    pub fn quantize_selection(&mut self) {
+        self.data.iter_mut().for_each(|(_, grid)| {
+            if grid.selected {
+                grid.offset = 0.;
+            }
+        });
+    }
+
+    // copy the selection right after itself (wrapping around the pattern),
+    // the copies become the new selection
+    // copies the selection right after itself and selects the copies, those past
+    // the end of the pattern or over another event are left out
+    pub fn duplicate_selection(&mut self) {
+        let selection: Vec<((usize, usize), GridEvent)> = self.data
+            .iter()
+            .filter(|(_, grid_event)| grid_event.selected)
+            .map(|(grid_id, grid_event)| (*grid_id, *grid_event))
+            .collect();
+
+        let first_step = selection.iter().map(|((step, _), _)| *step).min();
+        let last_step = selection.iter().map(|((step, _), _)| *step).max();
+
+        if let (Some(first_step), Some(last_step)) = (first_step, last_step) {
+            let span = last_step - first_step + 1;
+
+            let copies: Vec<((usize, usize), GridEvent)> = selection
+                .into_iter()
+                .map(|((step, track), grid_event)| ((step + span, track), grid_event))
+                .filter(|((step, track), _)| {
+                    *step < NUM_STEPS && !self.data.contains_key(&(*step, *track))
+                })
+                .collect();
+
+            // nothing fits, the selection is kept
+            if copies.is_empty() {
+                return;
+            }
+
+            self.empty_selection();
+
+            for (grid_id, grid_event) in copies {
+                self.data.insert(
+                    grid_id,
+                    GridEvent {
+                        selected: true,
+                        ..grid_event
+                    },
+                );
+            }
+        }
+    }
+
     pub fn move_selection_quantized(
         &self,
         bounds: Rectangle,
         drag_bounds: Rectangle,
         cursor: Point,
         origin_event: (usize, usize, GridEvent),
+        snap: Snap,
     ) -> (f32, isize) {
-        // cursor is normalized and padded, it cannto be outside : NOT TRUE ANYMORE
-        // so it must be hovering a step
-        // let step_offset: isize = hovered_step.0 as isize - origin_grid_id.0 as isize;
-
         let size = bounds.size();
         let step_size = get_step_dimensions(size);
         let hovered_track = get_hovered_track(cursor, bounds);
         let track_offset: isize = hovered_track as isize - origin_event.1 as isize;
 
-        let max_positive_offset: f32 = (NUM_STEPS - origin_event.0) as f32 - 1.;
-        let min_negative_offset: f32 = -1. * origin_event.0 as f32 - origin_event.2.offset;
+        // the drag moves by whole snap sizes, so events keep their micro-timing
+        let origin_position = origin_event.0 as f32 + origin_event.2.offset;
+        let step_offset = snap.snap(drag_bounds.width / step_size.width);
 
-        let mut selection_step_offset = {
-            if drag_bounds.width >= 0. {
-                (drag_bounds.width / step_size.width).floor()
-            } else {
-                (drag_bounds.width / step_size.width).ceil()
-            }
-        };
-
-        if origin_event.2.offset != 0. {
-            let step_offset = (drag_bounds.width / (step_size.width * 0.5)) as isize;
-            let wrapped_offset = step_offset % 2;
-            selection_step_offset = step_offset as f32 * 0.5;
-
-            if wrapped_offset != 0 {
-                if origin_event.2.offset < 0. {
-                    selection_step_offset =
-                        (step_offset as f32 * 0.5).floor() + (-1. * origin_event.2.offset);
-                } else {
-                    selection_step_offset =
-                        (step_offset as f32 * 0.5).floor() + (1. - origin_event.2.offset);
-                }
-            }
-        }
+        let max_positive_offset: f32 = (NUM_STEPS - 1) as f32 - origin_position;
+        let min_negative_offset: f32 = -1. * origin_position;
 
         (
-            selection_step_offset
+            step_offset
                 .min(max_positive_offset)
                 .max(min_negative_offset),
             track_offset,
         )
     }
 
+    // step offset bringing the earliest selected event to the next snap position
+    pub fn get_nudge_offset(&self, direction: isize, snap: Snap) -> f32 {
+        let size = snap.get_size().unwrap_or(OFFSET_THRESHOLD);
+        let origin_position = self
+            .data
+            .iter()
+            .filter(|(_, grid_event)| grid_event.selected)
+            .map(|((step, _), grid_event)| *step as f32 + grid_event.offset)
+            .fold(None, |min: Option<f32>, position| match min {
+                Some(min) => Some(min.min(position)),
+                None => Some(position),
+            });
+
+        match origin_position {
+            Some(position) => {
+                let index = position / size;
+                let next_index = match direction > 0 {
+                    true => (index + SNAP_EPSILON).floor() + direction as f32,
+                    false => (index - SNAP_EPSILON).ceil() + direction as f32,
+                };
+
+                next_index * size - position
+            }
+            None => 0.,
+        }
+    }
+
     pub fn move_selection_unquantized(
         &self,
         bounds: Rectangle,
@@ -299,6 +634,15 @@ impl GridPattern {
     }
 
     pub fn move_selection(&mut self, step_offset: f32, track_offset: isize) {
+        self.translate_selection(step_offset, track_offset, false);
+    }
+
+    // the originals stay in place and get unselected, the selected copies are moved
+    pub fn copy_selection(&mut self, step_offset: f32, track_offset: isize) {
+        self.translate_selection(step_offset, track_offset, true);
+    }
+
+    fn translate_selection(&mut self, step_offset: f32, track_offset: isize, keep_originals: bool) {
         // init empty hashmap
         let mut output: HashMap<(usize, usize), GridEvent> = HashMap::new();
 
@@ -306,6 +650,8 @@ impl GridPattern {
         for ((step, track), event) in self.data.to_owned() {
             if !event.selected {
                 output.insert((step, track), event);
+            } else if keep_originals {
+                output.insert((step, track), GridEvent { selected: false, ..event });
             }
         }
 
@@ -573,6 +919,85 @@ impl GridPattern {
             }
         });
     }
+
+    // every selected event is shifted by the same amount
+    pub fn shift_velocity(&mut self, delta: f32) {
+        self.data.iter_mut().for_each(|(_, event)| {
+            if event.selected {
+                event.velocity = (event.velocity + delta).min(1.).max(0.);
+            }
+        });
+    }
+
+    pub fn set_selection_velocity(&mut self, velocity: f32) {
+        self.data.iter_mut().for_each(|(_, event)| {
+            if event.selected {
+                event.velocity = velocity.min(1.).max(0.);
+            }
+        });
+    }
+
+    // the selection, or the whole pattern, relative to the grabbed step and track
+    pub fn get_drag_payload(&self, anchor: (usize, usize), selection_only: bool) -> DragPayload {
+        let events = self
+            .data
+            .iter()
+            .filter(|(_, event)| event.selected || !selection_only)
+            .map(|((step, track), event)| {
+                (
+                    (
+                        *step as isize - anchor.0 as isize,
+                        *track as isize - anchor.1 as isize,
+                    ),
+                    GridEvent { selected: true, ..*event },
+                )
+            })
+            .collect();
+
+        DragPayload { events }
+    }
+
+    // dropped events replace the ones they land on and become the selection,
+    // the ones falling outside of the pattern are lost
+    pub fn drop_events(&mut self, payload: &DragPayload, target: (usize, usize)) {
+        self.empty_selection();
+
+        for ((step_offset, track_offset), event) in payload.events.iter() {
+            let step = target.0 as isize + step_offset;
+            let track = target.1 as isize + track_offset;
+
+            if (0..NUM_STEPS as isize).contains(&step) && (0..NUM_PERCS as isize).contains(&track) {
+                self.data.insert((step as usize, track as usize), *event);
+            }
+        }
+    }
+}
+
+/// Events dragged out of a [`Grid`] or a [`SnapshotView`], keyed by their
+/// (step, track) distance to the grabbed step.
+///
+/// [`Grid`]: crate::native::grid::Grid
+/// [`SnapshotView`]: crate::native::snapshot_view::SnapshotView
+#[derive(Debug, Clone, Default)]
+pub struct DragPayload {
+    pub events: Vec<((isize, isize), GridEvent)>,
+}
+
+/// Drag and drop between widgets.
+///
+/// The host keeps the `Picked` payload and hands it to every [`Grid`] that
+/// accepts drops with [`Grid::drag_payload`]. On release the grid under the
+/// cursor sends `Dropped` with the (step, track) the grabbed step lands on,
+/// and the widget the drag started from sends `Canceled()`, after which the
+/// host can forget the payload: `Dropped` carries its own copy.
+///
+/// [`Grid`]: crate::native::grid::Grid
+/// [`Grid::drag_payload`]: crate::native::grid::Grid::drag_payload
+#[derive(Debug, Clone)]
+pub enum DragMessage {
+    Picked(DragPayload),
+    Dropped(DragPayload, (usize, usize)), // => COMMITS STATE with manage_drop
+    Canceled(),
 }
 
 impl From<Pattern> for GridPattern {
@@ -626,20 +1051,43 @@ pub enum GridMessage {
     ToggleOne((usize, usize)), // just mutate selection
     AddOneToSelection((usize, usize)), // Add one to the selection
     AddSelectedArea(Rectangle, Size), // Add events from area to the selection
+    RemoveSelectedArea(Rectangle, Size), // Remove events from area from the selection
     SelectOne((usize, usize)), // empty selection, select new one
     SelectArea(Rectangle, Size), // empty selection, select events from area
     SelectAll(),
     EmptySelection(),
     ToggleArea(Rectangle, Size),
-    MoveSelection((f32, isize), bool),
+    MoveSelection((f32, isize)), // nudge the selection => COMMITS STATE
+    NudgeSelection(isize, Snap), // nudge the selection to the next snap position => COMMITS STATE
+    MoveStarted(bool), // a drag grabbed the selection, true when dragging copies of it
+    MoveUpdated((f32, isize)), // preview only, relative to the base pattern => MoveCommitted
+    MoveCommitted(),
     DeleteSelection(),
-    SetVelocity(f32),
+    SetVelocity(f32), // scale the selection velocities => COMMITS STATE
+    PreviewVelocity(f32), // preview only, SetVelocity from the base pattern => CommitState
+    ShiftVelocity(f32), // preview only, relative to the base pattern => CommitState
     TrackSelected(usize),
+    SelectTrack(usize), // select every event of a track
+    SelectColumn(usize), // select every event of a step
+    SelectSimilar(), // same tracks as the selection, close velocities
+    InvertSelection(),
+    SelectEveryNthStep(usize, usize), // (n, first step)
+    ClearTrack(usize), // delete every event of a track
+    DuplicateSelection(),
+    QuantizeSelection(),
+    SetSelectionVelocity(f32), // absolute velocity for the whole selection
     CommitState(),
     DiscardState(),
 }
 
 
+/// Applies a [`GridMessage`] emitted by the grid to the host's pattern.
+///
+/// Most messages commit the result to the base pattern right away. Previews,
+/// like `MoveUpdated`, `PreviewVelocity` and `ShiftVelocity`, only change the
+/// live pattern and are always computed from the base pattern: the grid sends
+/// `CommitState` (or `MoveCommitted`) when the drag ends and `DiscardState`
+/// when it is cancelled.
 pub fn manage_state_update(
     message: GridMessage,
     state: &mut State,
@@ -681,6 +1129,11 @@ pub fn manage_state_update(
             live_pattern.data = next_grid.data.clone();
             state.set_pattern(next_grid);
         },
+        GridMessage::RemoveSelectedArea(selection, bounds) => {
+            next_grid.remove_selected_area(selection, bounds);
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
         GridMessage::SelectOne(grid_id) => {
             next_grid.select_one(grid_id);
             live_pattern.data = next_grid.data.clone();
@@ -701,18 +1154,33 @@ pub fn manage_state_update(
             live_pattern.data = next_grid.data.clone();
             state.set_pattern(next_grid);
         },
-        GridMessage::MoveSelection(next_movement, relative) => {
-            state.set_movement(next_movement, relative);
+        GridMessage::MoveSelection((step_offset, track_offset)) => {
+            next_grid.move_selection(step_offset, track_offset);
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::NudgeSelection(direction, snap) => {
+            let step_offset = next_grid.get_nudge_offset(direction, snap);
+            next_grid.move_selection(step_offset, 0);
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::MoveStarted(copy) => {
+            state.start_movement(copy);
+        },
+        GridMessage::MoveUpdated(movement) => {
+            state.set_movement(movement);
 
-            match state.get_movement() {
-                Some(movement) => {
-                    next_grid.move_selection(movement.0, movement.1);
-                },
-                None => {},
+            match state.is_copying() {
+                true => next_grid.copy_selection(movement.0, movement.1),
+                false => next_grid.move_selection(movement.0, movement.1),
             }
 
             live_pattern.data = next_grid.data;
         },
+        GridMessage::MoveCommitted() => {
+            state.set_pattern(live_pattern.clone());
+        },
         GridMessage::DeleteSelection() => {
             next_grid.remove_selection();
             live_pattern.data = next_grid.data.clone();
@@ -723,13 +1191,368 @@ pub fn manage_state_update(
             live_pattern.data = next_grid.data.clone();
             state.set_pattern(next_grid);
         },
+        GridMessage::PreviewVelocity(ratio) => {
+            next_grid.set_velocity(ratio);
+            live_pattern.data = next_grid.data;
+        },
+        GridMessage::ShiftVelocity(delta) => {
+            next_grid.shift_velocity(delta);
+            live_pattern.data = next_grid.data;
+        },
+        GridMessage::SelectTrack(track) => {
+            next_grid.select_track(track);
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::SelectColumn(step) => {
+            next_grid.select_column(step);
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::SelectSimilar() => {
+            next_grid.select_similar();
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::InvertSelection() => {
+            next_grid.invert_selection();
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::SelectEveryNthStep(n, offset) => {
+            next_grid.select_every_nth_step(n, offset);
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::ClearTrack(track) => {
+            next_grid.clear_track(track);
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::DuplicateSelection() => {
+            next_grid.duplicate_selection();
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::QuantizeSelection() => {
+            next_grid.quantize_selection();
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
+        GridMessage::SetSelectionVelocity(velocity) => {
+            next_grid.set_selection_velocity(velocity);
+            live_pattern.data = next_grid.data.clone();
+            state.set_pattern(next_grid);
+        },
         GridMessage::TrackSelected(track) => {
             *focused_track = NUM_PERCS - track - 1;
         },
+        GridMessage::CommitState() => {
+            state.set_pattern(live_pattern.clone());
+        },
         GridMessage::DiscardState() => {
             live_pattern.data = state.clone_base_pattern().data;
+            state.discard_movement();
         },
-        _ => {}
     }
 }
 
+pub fn manage_drop(
+    payload: &DragPayload,
+    target: (usize, usize),
+    state: &mut State,
+    live_pattern: &mut GridPattern,
+) {
+    let mut next_grid = state.clone_base_pattern();
+
+    next_grid.drop_events(payload, target);
+    live_pattern.data = next_grid.data.clone();
+    state.set_pattern(next_grid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_event(selected: bool) -> GridEvent {
+        GridEvent {
+            selected,
+            ..GridEvent::default()
+        }
+    }
+
+    fn get_pattern(events: &[((usize, usize), bool)]) -> GridPattern {
+        let mut pattern = GridPattern::new();
+
+        for (grid_id, selected) in events {
+            pattern.data.insert(*grid_id, get_event(*selected));
+        }
+
+        pattern
+    }
+
+    fn get_sorted_selection(pattern: &GridPattern) -> Vec<(usize, usize)> {
+        let mut selection = pattern.get_selection();
+        selection.sort();
+
+        selection
+    }
+
+    #[test]
+    fn duplicate_selection_after_itself() {
+        let mut pattern = get_pattern(&[((0, 0), true), ((1, 2), true)]);
+
+        pattern.duplicate_selection();
+
+        assert_eq!(pattern.data.len(), 4);
+        assert_eq!(get_sorted_selection(&pattern), vec![(2, 0), (3, 2)]);
+    }
+
+    #[test]
+    fn duplicate_selection_does_not_wrap_around() {
+        let half = NUM_STEPS / 2;
+        let mut pattern = get_pattern(&[((0, 0), true), ((half, 0), true)]);
+
+        pattern.duplicate_selection();
+
+        // the copy of the last event would land past the end, on step 1
+        assert_eq!(get_sorted_selection(&pattern), vec![(half + 1, 0)]);
+        assert!(!pattern.data.contains_key(&(1, 0)));
+        assert!(!pattern.data[&(0, 0)].selected);
+        assert!(!pattern.data[&(half, 0)].selected);
+    }
+
+    #[test]
+    fn duplicate_selection_spanning_the_pattern_is_kept() {
+        let mut pattern = get_pattern(&[((0, 0), true), ((NUM_STEPS - 1, 0), true)]);
+
+        pattern.duplicate_selection();
+
+        assert_eq!(pattern.data.len(), 2);
+        assert_eq!(get_sorted_selection(&pattern), vec![(0, 0), (NUM_STEPS - 1, 0)]);
+    }
+
+    #[test]
+    fn duplicate_selection_keeps_other_events() {
+        let mut pattern = get_pattern(&[((0, 1), true), ((1, 1), true), ((2, 1), false)]);
+        pattern.data.get_mut(&(2, 1)).unwrap().velocity = 0.5;
+
+        pattern.duplicate_selection();
+
+        assert_eq!(get_sorted_selection(&pattern), vec![(3, 1)]);
+        assert!(!pattern.data[&(2, 1)].selected);
+        assert_eq!(pattern.data[&(2, 1)].velocity, 0.5);
+    }
+
+    #[test]
+    fn duplicate_empty_selection() {
+        let mut pattern = get_pattern(&[((0, 0), false)]);
+
+        pattern.duplicate_selection();
+
+        assert_eq!(pattern.data.len(), 1);
+        assert!(pattern.get_selection().is_empty());
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 0.0001, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn snap_to_whole_steps() {
+        assert_close(Snap::Whole.snap(1.4), 1.);
+        assert_close(Snap::Whole.snap(1.6), 2.);
+        assert_close(Snap::Whole.snap(-0.6), -1.);
+    }
+
+    #[test]
+    fn snap_to_triplets() {
+        assert_close(Snap::Third.snap(0.3), 1. / 3.);
+        assert_close(Snap::Third.snap(0.6), 2. / 3.);
+        assert_close(Snap::Third.snap(1.1), 1.);
+    }
+
+    #[test]
+    fn snap_to_custom_fractions() {
+        assert_close(Snap::Custom(0.2).snap(0.45), 0.4);
+        assert_close(Snap::Custom(0.2).snap(0.55), 0.6);
+
+        // not a fraction, nothing snaps
+        assert_close(Snap::Custom(0.).snap(0.45), 0.45);
+        assert_close(Snap::Custom(-1.).snap(0.45), 0.45);
+        assert_close(Snap::Off.snap(0.45), 0.45);
+    }
+
+    #[test]
+    fn nudge_to_the_next_snap_position() {
+        let mut pattern = get_pattern(&[((2, 0), true), ((5, 1), true)]);
+
+        // from the earliest selected event
+        assert_close(pattern.get_nudge_offset(1, Snap::Whole), 1.);
+        assert_close(pattern.get_nudge_offset(-1, Snap::Whole), -1.);
+
+        pattern.data.get_mut(&(2, 0)).unwrap().offset = 0.1;
+
+        assert_close(pattern.get_nudge_offset(1, Snap::Third), 7. / 3. - 2.1);
+        assert_close(pattern.get_nudge_offset(-1, Snap::Third), -0.1);
+        assert_close(pattern.get_nudge_offset(1, Snap::Off), OFFSET_THRESHOLD);
+    }
+
+    #[test]
+    fn nudge_past_the_pattern_start_wraps_around() {
+        let mut pattern = get_pattern(&[((0, 0), true)]);
+
+        let step_offset = pattern.get_nudge_offset(-1, Snap::Whole);
+        assert_close(step_offset, -1.);
+
+        pattern.move_selection(step_offset, 0);
+        assert_eq!(pattern.get_selection(), vec![(NUM_STEPS - 1, 0)]);
+    }
+
+    #[test]
+    fn nudge_empty_selection() {
+        let pattern = get_pattern(&[((2, 0), false)]);
+
+        assert_close(pattern.get_nudge_offset(1, Snap::Whole), 0.);
+        assert_close(pattern.get_nudge_offset(-1, Snap::Half), 0.);
+    }
+
+    #[test]
+    fn select_similar_velocities_on_the_selected_tracks() {
+        let mut pattern = get_pattern(&[
+            ((0, 0), true),
+            ((1, 0), false),
+            ((2, 0), false),
+            ((0, 1), false),
+        ]);
+
+        for (grid_id, velocity) in [((0, 0), 0.5), ((1, 0), 0.55), ((2, 0), 0.7), ((0, 1), 0.5)] {
+            pattern.data.get_mut(&grid_id).unwrap().velocity = velocity;
+        }
+
+        pattern.select_similar();
+
+        assert_eq!(get_sorted_selection(&pattern), vec![(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn select_similar_without_selection() {
+        let mut pattern = get_pattern(&[((0, 0), false), ((1, 0), false)]);
+
+        pattern.select_similar();
+
+        assert!(pattern.get_selection().is_empty());
+    }
+
+    #[test]
+    fn select_every_nth_step_from_an_offset() {
+        let mut pattern = get_pattern(&[
+            ((0, 0), true),
+            ((2, 0), false),
+            ((3, 1), false),
+            ((6, 2), false),
+        ]);
+
+        pattern.select_every_nth_step(4, 2);
+
+        assert_eq!(get_sorted_selection(&pattern), vec![(2, 0), (6, 2)]);
+    }
+
+    #[test]
+    fn select_every_nth_step_out_of_the_pattern() {
+        let mut pattern = get_pattern(&[((0, 0), true), ((NUM_STEPS - 1, 0), false)]);
+
+        // no step is every 0th
+        pattern.select_every_nth_step(0, 0);
+        assert!(pattern.get_selection().is_empty());
+
+        pattern.select_every_nth_step(1, NUM_STEPS);
+        assert!(pattern.get_selection().is_empty());
+
+        pattern.select_every_nth_step(1, NUM_STEPS - 1);
+        assert_eq!(pattern.get_selection(), vec![(NUM_STEPS - 1, 0)]);
+    }
+
+    #[test]
+    fn deselect_area() {
+        let size = Size::new(900., 300.);
+        let mut pattern = get_pattern(&[((2, 1), true), ((3, 1), true), ((2, 2), false)]);
+
+        // inside the event on step 2 of track 1 only
+        let event_bounds = get_event_content_bounds(2, 1, 0., size);
+        let selection = Rectangle {
+            x: event_bounds.x + 1.,
+            y: event_bounds.y + 1.,
+            width: event_bounds.width - 2.,
+            height: event_bounds.height - 2.,
+        };
+
+        pattern.remove_selected_area(selection, size);
+
+        assert_eq!(get_sorted_selection(&pattern), vec![(3, 1)]);
+        assert!(!pattern.data[&(2, 2)].selected);
+    }
+
+    #[test]
+    fn deselect_empty_area() {
+        let size = Size::new(900., 300.);
+        let mut pattern = get_pattern(&[((2, 1), true)]);
+
+        // over the first edge step, where no event can be
+        let selection = Rectangle::new(Point::new(1., 1.), Size::new(4., 4.));
+        pattern.remove_selected_area(selection, size);
+
+        assert_eq!(pattern.get_selection(), vec![(2, 1)]);
+    }
+
+    fn get_payload(offsets: &[(isize, isize)]) -> DragPayload {
+        DragPayload {
+            events: offsets.iter().map(|offset| (*offset, get_event(true))).collect(),
+        }
+    }
+
+    #[test]
+    fn drop_events_replace_and_become_the_selection() {
+        let mut pattern = get_pattern(&[((0, 0), true), ((4, 2), false)]);
+        pattern.data.get_mut(&(4, 2)).unwrap().velocity = 0.5;
+
+        pattern.drop_events(&get_payload(&[(0, 0), (1, 0)]), (4, 2));
+
+        assert_eq!(get_sorted_selection(&pattern), vec![(4, 2), (5, 2)]);
+        assert_eq!(pattern.data[&(4, 2)].velocity, DEFAULT_VELOCITY);
+        assert!(!pattern.data[&(0, 0)].selected);
+    }
+
+    #[test]
+    fn drop_events_past_the_pattern_boundaries() {
+        let mut pattern = GridPattern::new();
+        let target = (NUM_STEPS - 1, NUM_PERCS - 1);
+
+        pattern.drop_events(&get_payload(&[(0, 0), (-1, 0), (1, 0), (0, 1), (0, -1)]), target);
+
+        // nothing wraps around
+        assert_eq!(
+            get_sorted_selection(&pattern),
+            vec![
+                (NUM_STEPS - 2, NUM_PERCS - 1),
+                (NUM_STEPS - 1, NUM_PERCS - 2),
+                (NUM_STEPS - 1, NUM_PERCS - 1),
+            ]
+        );
+
+        let mut pattern = GridPattern::new();
+        pattern.drop_events(&get_payload(&[(-1, 0), (0, -1)]), (0, 0));
+
+        assert!(pattern.data.is_empty());
+    }
+
+    #[test]
+    fn drop_empty_payload() {
+        let mut pattern = get_pattern(&[((0, 0), true)]);
+
+        pattern.drop_events(&DragPayload::default(), (0, 0));
+
+        assert_eq!(pattern.data.len(), 1);
+        assert!(pattern.get_selection().is_empty());
+    }
+}
@@ -1,25 +1,61 @@
-use crate::{core::grid::GridEvent, native::grid};
+use std::time::Duration;
+
+use crate::native::grid;
 use iced_core::mouse;
-use iced_graphics::canvas::{Cache, Frame, Geometry, LineCap, Path, Stroke};
+use iced_graphics::canvas::{self, Cache, Frame, Geometry, LineCap, Path, Stroke};
 use iced_graphics::{Backend, Primitive, Renderer};
-use iced_native::Background;
+use iced_native::{alignment, Background, Color, Font};
+use iced_native::renderer::Renderer as _;
 
-use crate::core::grid::{
-    get_event_bounds, get_step_dimensions,
-    GridPattern, TRACK_MARGIN_BOTTOM
-};
+use crate::core::grid::{get_step_dimensions, GridPattern, Snap, Viewport};
 use iced_native::{Point, Rectangle, Size, Vector};
 
-pub use crate::native::grid::State;
-pub use crate::style::color_utils::{darken, lighten};
-pub use crate::style::grid::{Style, StyleSheet, GridColor};
+pub use crate::native::grid::{Layers, State, Status};
+use crate::native::grid::context_menu::{get_item_bounds, MenuAction, MENU_PADDING};
+use crate::native::grid::geometry::{self, Shape};
+use crate::native::grid::tooltip::{TOOLTIP_LINE_HEIGHT, TOOLTIP_PADDING};
+pub use crate::style::color_utils::{darken, lighten, mix};
+pub use crate::style::grid::{Flash, Style, StyleSheet, GridColor, VelocityDisplay};
 
 use ganic_no_std::{NUM_PERCS, NUM_STEPS};
 
 pub type Grid<'a, Message, Backend> = grid::Grid<'a, Message, Renderer<Backend>>;
 
-const BEATS: usize = 4;
-const BEAT_STEP_COUNT: usize = NUM_STEPS / BEATS;
+// a canvas cache, and the generation of the layer it was drawn at
+#[derive(Debug, Default)]
+struct CachedLayer {
+    cache: Cache,
+    generation: u64,
+}
+
+impl CachedLayer {
+    fn get(&mut self, generation: u64) -> &Cache {
+        if self.generation != generation {
+            self.cache.clear();
+            self.generation = generation;
+        }
+
+        &self.cache
+    }
+}
+
+// kept in the layers of each grid state
+#[derive(Debug)]
+struct Caches {
+    grid: CachedLayer,
+    steps: Vec<CachedLayer>, // the played events are left out
+    highlight: CachedLayer,
+}
+
+impl Default for Caches {
+    fn default() -> Self {
+        Caches {
+            grid: CachedLayer::default(),
+            steps: (0..NUM_STEPS).map(|_| CachedLayer::default()).collect(),
+            highlight: CachedLayer::default(),
+        }
+    }
+}
 
 impl<B: Backend> grid::Renderer for Renderer<B> {
     type Style = Box<dyn StyleSheet>;
@@ -28,72 +64,132 @@ impl<B: Backend> grid::Renderer for Renderer<B> {
         &mut self,
         bounds: Rectangle,
         drawable_area: Rectangle,
-        _cursor_position: Point,
+        cursor_position: Point,
         grid_pattern: &GridPattern,
+        ghost_pattern: Option<&GridPattern>,
+        viewport: Viewport,
+        snap: Snap,
+        status: Status,
+        focused_track: Option<usize>,
         selection: Option<Rectangle>,
+        is_deselecting: bool,
+        velocity_readout: Option<f32>,
         _mouse_interaction: mouse::Interaction,
         is_playing: bool,
         highlight: [usize; NUM_PERCS],
+        playhead: Option<f32>,
+        flashes: [Option<(usize, Duration)>; NUM_PERCS],
         mutes: [bool; NUM_PERCS],
+        is_focused: bool,
         style_sheet: &Self::Style,
-        grid_cache: &Cache,
-        event_cache: &Cache,
-        _highlight_cache: &Cache,
+        layers: &Layers,
     ) {
-        let style = style_sheet.default();
-        let step_size = get_step_dimensions(drawable_area.size());
+        let style = get_style(style_sheet.as_ref(), status);
+
+        // the whole pattern is cached at the zoomed size, scrolling only translates it
+        let content_size = viewport.get_content_size(drawable_area.size());
+        let scroll_offset = viewport.get_scroll_offset(drawable_area.size());
+        let step_size = get_step_dimensions(content_size);
+
+        // the played events are left to the highlight layer, so a transport tick
+        // only redraws the steps it leaves and reaches
+        let played = if is_playing { Some(highlight) } else { None };
+
+        let mut content_primitives = layers.with_storage(|caches: &mut Caches| {
+            // 1. grid
+            let grid = caches.grid.get(layers.grid()).draw(content_size, |frame| {
+                draw_shapes(
+                    frame,
+                    &geometry::grid(content_size, step_size, snap, focused_track, &style),
+                )
+            });
+            let mut primitives = vec![grid.into_primitive()];
+
+            // 2. events, and where the moved ones come from
+            for (step, cached_step) in caches.steps.iter_mut().enumerate() {
+                let steps = cached_step.get(layers.step(step)).draw(content_size, |frame| {
+                    draw_shapes(
+                        frame,
+                        &geometry::steps(
+                            content_size,
+                            grid_pattern,
+                            Some(step),
+                            step_size,
+                            played,
+                            mutes,
+                            &style,
+                        ),
+                    )
+                });
+
+                primitives.push(steps.into_primitive());
+            }
 
-        let grid = grid_cache.draw(drawable_area.size(), |frame| {
-            draw_grid(
-                frame,
-                drawable_area.size(),
-                step_size,
-                is_playing,
-                highlight,
-                &style,
-            )
-        });
-        let steps = event_cache.draw(drawable_area.size(), |frame| {
-            draw_steps(
-                frame,
-                drawable_area.size(),
-                grid_pattern,
-                step_size,
-                is_playing,
-                highlight,
-                mutes,
-                &style,
-            )
+            if let (Some(ghost_pattern), Some(ghost_stroke)) = (ghost_pattern, style.ghost_stroke) {
+                primitives.push(draw_frame(
+                    content_size,
+                    &geometry::ghosts(content_size, ghost_pattern, step_size, ghost_stroke),
+                ));
+            }
+
+            // 3. highlighted steps and their events, on their own cache
+            if is_playing {
+                let played_steps = caches.highlight.get(layers.highlight()).draw(content_size, |frame| {
+                    if style.highlight_current_step {
+                        draw_shapes(frame, &geometry::highlight(content_size, highlight, mutes, &style));
+                    }
+
+                    draw_shapes(
+                        frame,
+                        &geometry::played_steps(
+                            content_size,
+                            grid_pattern,
+                            step_size,
+                            highlight,
+                            mutes,
+                            &style,
+                        ),
+                    )
+                });
+
+                primitives.push(played_steps.into_primitive());
+            }
+
+            primitives
         });
 
-        // 1. grid
-        let mut canvas_primitives = vec![grid.into_primitive()];
+        // animated, never cached
+        if let (true, Some(flash)) = (is_playing, style.event.flash) {
+            content_primitives.push(draw_frame(
+                content_size,
+                &geometry::flashes(content_size, grid_pattern, step_size, flashes, mutes, flash),
+            ));
+        }
 
-        // 2. highlighted steps
-        if is_playing {
-            canvas_primitives.push(draw_highlight(drawable_area.size(), highlight, mutes, &style));
+        // 4. playhead, above the events it plays
+        if let (true, Some(position), Some(stroke)) = (is_playing, playhead, style.playhead) {
+            content_primitives.push(draw_frame(
+                content_size,
+                &[geometry::playhead(content_size, position, stroke)],
+            ));
         }
 
-        // 3. events
-        canvas_primitives.push(steps.into_primitive());
+        let mut canvas_primitives = vec![Primitive::Translate {
+            translation: Vector::new(-scroll_offset, 0.),
+            content: Box::new(Primitive::Group { primitives: content_primitives }),
+        }];
 
-        // 4. selection
-        match selection {
-            Some(selection) => {
-                canvas_primitives.push(draw_selection(selection, drawable_area, &style));
-            }
-            None => {}
+        // 5. selection
+        if let Some(selection) = selection {
+            canvas_primitives.push(draw_frame(
+                drawable_area.size(),
+                &geometry::selection(selection, is_deselecting, &style),
+            ));
         }
 
-        let mut primitives = vec![
-            Primitive::Translate {
-                translation: Vector::new(drawable_area.x, drawable_area.y),
-                content: Box::new(Primitive::Group { primitives: canvas_primitives }),
-            }
-        ];
+        let mut primitives = vec![];
 
-        if style.background.is_some() {
-            let background = style.background.unwrap();
+        if let Some(background) = style.background {
             primitives.push(Primitive::Quad {
                 bounds,
                 background: Background::Color(background.bg_color),
@@ -103,338 +199,218 @@ impl<B: Backend> grid::Renderer for Renderer<B> {
             });
         }
 
-        // (
-        //     Primitive::Group { primitives },
-        //     mouse_interaction,
-        // )
-
-        self.draw_primitive(Primitive::Group { primitives })
-    }
-}
-
-fn draw_selection(selection: Rectangle, bounds: Rectangle, style: &Style) -> Primitive {
-    let mut frame = Frame::new(bounds.size());
-
-    let top_left = Point {
-        x: selection.x,
-        y:  selection.y
-    };
+        self.draw_primitive(Primitive::Group { primitives });
 
-    let area = Path::rectangle(top_left, selection.size());
+        // steps scrolled out of the drawable area are clipped
+        self.with_layer(drawable_area, |renderer| {
+            renderer.draw_primitive(Primitive::Translate {
+                translation: Vector::new(drawable_area.x, drawable_area.y),
+                content: Box::new(Primitive::Group { primitives: canvas_primitives }),
+            })
+        });
 
-    frame.stroke(
-        &area,
-        Stroke {
-            width: style.selection_stroke.line_width,
-            color: style.selection_stroke.color,
-            line_cap: LineCap::Square,
-            ..Stroke::default()
-        },
-    );
+        if let Some(velocity) = velocity_readout {
+            let readout_bounds = geometry::get_readout_bounds(cursor_position);
 
-    Geometry::into_primitive(frame.into_geometry())
-}
+            self.with_layer(readout_bounds, |renderer| {
+                renderer.draw_primitive(draw_readout(
+                    readout_bounds,
+                    format!("{}%", (velocity * 100.).round()),
+                    &style,
+                ))
+            });
+        }
 
-fn draw_grid(
-    frame: &mut Frame,
-    size: Size,
-    step_size: Size,
-    _is_playing: bool,
-    _highlight: [usize; NUM_PERCS],
-    style: &Style,
-) {
-    // backgrounds
-    for beat in 0..BEATS {
-        // bg color definition
-        let bg_color = match beat {
-            0 | 2 => style.grid.even_beat_bg_color,
-            _ => style.grid.odd_beat_bg_color
-        };
-
-        let beat_width = step_size.width * BEAT_STEP_COUNT as f32;
-        let beat_origin = Point {
-            x: beat as f32 * beat_width + step_size.width,
-            y: 0.,
-        };
-        let beat_size = Size {
-            width: beat_width,
-            height: size.height,
-        };
-
-        let beat_bg = Path::rectangle(beat_origin, beat_size);
-
-        frame.fill(&beat_bg, bg_color);
+        // focus ring, on its own layer to stay above the steps
+        if let (true, Some(focus_ring)) = (is_focused, style.focus_ring) {
+            let border_radius = style
+                .background
+                .map_or(0., |background| background.border_radius);
+
+            self.with_layer(bounds, |renderer| {
+                renderer.draw_primitive(Primitive::Quad {
+                    bounds,
+                    background: Background::Color(Color::TRANSPARENT),
+                    border_radius,
+                    border_width: focus_ring.line_width,
+                    border_color: focus_ring.color,
+                })
+            });
+        }
     }
 
-    // edges bg
-    let start_edge = Point { x: 0., y: 0. };
-    let start_edge_size = Size { width: step_size.width, height: size.height };
-    let start_bg = Path::rectangle(start_edge, start_edge_size);
-    frame.fill(&start_bg, style.grid.edge_step_bg_color);
+    fn draw_context_menu(
+        &mut self,
+        bounds: Rectangle,
+        actions: &[MenuAction],
+        hovered: Option<usize>,
+        style_sheet: &Self::Style,
+    ) {
+        let style = style_sheet.default().context_menu;
+
+        let mut primitives = vec![Primitive::Quad {
+            bounds,
+            background: Background::Color(style.background.bg_color),
+            border_radius: style.background.border_radius,
+            border_width: style.background.border_width,
+            border_color: style.background.border_color,
+        }];
+
+        for (index, action) in actions.iter().enumerate() {
+            let item_bounds = get_item_bounds(bounds, index);
+
+            let text_color = if hovered == Some(index) {
+                primitives.push(Primitive::Quad {
+                    bounds: item_bounds,
+                    background: Background::Color(style.hovered_bg_color),
+                    border_radius: 0.,
+                    border_width: 0.,
+                    border_color: style.hovered_bg_color,
+                });
+
+                style.hovered_text_color
+            } else {
+                style.text_color
+            };
+
+            primitives.push(Primitive::Text {
+                content: action.label(),
+                bounds: Rectangle {
+                    x: item_bounds.x + 2. * MENU_PADDING,
+                    y: item_bounds.center_y(),
+                    width: item_bounds.width - 4. * MENU_PADDING,
+                    ..item_bounds
+                },
+                color: text_color,
+                size: style.text_size,
+                font: Font::Default,
+                horizontal_alignment: alignment::Horizontal::Left,
+                vertical_alignment: alignment::Vertical::Center,
+            });
+        }
 
-    let end_edge = Point { x: (NUM_STEPS + 1) as f32 * step_size.width, y: 0. };
-    let end_edge_size = Size { width: step_size.width, height: size.height };
-    let end_bg = Path::rectangle(end_edge, end_edge_size);
-    frame.fill(&end_bg, style.grid.edge_step_bg_color);
+        self.draw_primitive(Primitive::Group { primitives })
+    }
 
-    // track margins
-    for track in 0..NUM_PERCS {
-        let track_margin_origin = Point {
-            x: 0.,
-            y: track as f32 * (step_size.height + TRACK_MARGIN_BOTTOM) + step_size.height as f32,
-        };
+    fn draw_tooltip(&mut self, bounds: Rectangle, lines: &[String], style_sheet: &Self::Style) {
+        let style = style_sheet.default().tooltip;
+
+        let mut primitives = vec![Primitive::Quad {
+            bounds,
+            background: Background::Color(style.background.bg_color),
+            border_radius: style.background.border_radius,
+            border_width: style.background.border_width,
+            border_color: style.background.border_color,
+        }];
+
+        for (index, line) in lines.iter().enumerate() {
+            primitives.push(Primitive::Text {
+                content: line.clone(),
+                bounds: Rectangle {
+                    x: bounds.x + 2. * TOOLTIP_PADDING,
+                    y: bounds.y + TOOLTIP_PADDING + (index as f32 + 0.5) * TOOLTIP_LINE_HEIGHT,
+                    width: bounds.width - 4. * TOOLTIP_PADDING,
+                    height: TOOLTIP_LINE_HEIGHT,
+                },
+                color: style.text_color,
+                size: style.text_size,
+                font: Font::Default,
+                horizontal_alignment: alignment::Horizontal::Left,
+                vertical_alignment: alignment::Vertical::Center,
+            });
+        }
 
-        let track_margin_size = Size {
-            width: size.width,
-            height: TRACK_MARGIN_BOTTOM,
-        };
+        self.draw_primitive(Primitive::Group { primitives })
+    }
 
-        let track_margin_bg = Path::rectangle(track_margin_origin, track_margin_size);
+    fn restyle(style_sheet: &Self::Style, status: Status, layers: &Layers) {
+        layers.restyle(&get_style(style_sheet.as_ref(), status));
+    }
 
-        frame.fill(&track_margin_bg, style.grid.track_margin_color);
+    fn event_inset(&self, style_sheet: &Self::Style) -> f32 {
+        style_sheet.default().event.contour_width
     }
+}
 
-    for step in 0..=NUM_STEPS {
-        // stroke style definition
-        let stroke = match step {
-            0 | NUM_STEPS => style.grid.edge_step_line,
-            step_index if (step_index / BEAT_STEP_COUNT) % 2 == 1 => style.grid.odd_beat_line,
-            _ => style.grid.even_beat_line
-        };
+fn get_style(style_sheet: &dyn StyleSheet, status: Status) -> Style {
+    match status {
+        Status::Disabled => style_sheet.disabled(),
+        Status::Dragging => style_sheet.dragging_selection(),
+        Status::Focused => style_sheet.focused(),
+        Status::Hovered => style_sheet.hovered(),
+        Status::Active => style_sheet.default(),
+    }
+}
 
-        let step_offset_x = (step + 1) as f32 * step_size.width;
+fn draw_readout(bounds: Rectangle, content: String, style: &Style) -> Primitive {
+    let readout = style.readout;
 
-        let step_line = Path::line(
-            Point {
-                x: step_offset_x,
-                y: 0.,
-            },
-            Point {
-                x: step_offset_x,
-                y: size.height - TRACK_MARGIN_BOTTOM,
+    Primitive::Group {
+        primitives: vec![
+            Primitive::Quad {
+                bounds,
+                background: Background::Color(readout.background.bg_color),
+                border_radius: readout.background.border_radius,
+                border_width: readout.background.border_width,
+                border_color: readout.background.border_color,
             },
-        );
-
-        frame.stroke(
-            &step_line,
-            Stroke {
-                width: stroke.line_width,
-                color: stroke.color,
-                line_cap: LineCap::Square,
-                ..Stroke::default()
+            Primitive::Text {
+                content,
+                bounds: Rectangle {
+                    x: bounds.center_x(),
+                    y: bounds.center_y(),
+                    ..bounds
+                },
+                color: readout.text_color,
+                size: readout.text_size,
+                font: Font::Default,
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
             },
-        );
+        ],
     }
 }
 
-fn draw_highlight(
-    size: Size,
-    highlight: [usize; NUM_PERCS],
-    mutes: [bool; NUM_PERCS],
-    style: &Style,
-) -> Primitive {
+fn draw_frame(size: Size, shapes: &[Shape]) -> Primitive {
     let mut frame = Frame::new(size);
-
-    let highlighted_steps = Path::new(|path| {
-        for (track , highlighted_step) in highlight.iter().enumerate() {
-            if mutes[track] {
-                continue;
-            }
-
-            let event_bounds = get_event_bounds(*highlighted_step, track, 0., size);
-            path.rectangle(event_bounds.position(), event_bounds.size());
-        }
-
-        path.close();
-    });
-
-    frame.fill(&highlighted_steps, style.current_step_bg_color);
+    draw_shapes(&mut frame, shapes);
 
     Geometry::into_primitive(frame.into_geometry())
 }
 
-fn draw_steps(
-    frame: &mut Frame,
-    size: Size,
-    grid_pattern: &GridPattern,
-    step_size: Size,
-    is_playing: bool,
-    highlight: [usize; NUM_PERCS],
-    mutes: [bool; NUM_PERCS],
-    style: &Style,
-) {
-    let mut events: Vec<(usize, usize, GridEvent)> = grid_pattern
-        .data
-        .iter()
-        .map(|((step, track), grid_event)| (*step, *track, *grid_event))
-        .collect();
-
-    events.sort_by(|x, y| {
-        if x.1 == y.1 {
-            return x.0.cmp(&y.0);
-        }
-        x.1.cmp(&y.1)
-    });
-
-    let selected_events: Vec<&(usize, usize, GridEvent)> =
-        events.iter().filter(|(_, _, e)| e.selected).collect();
-
-    let mut sorted_events: Vec<&(usize, usize, GridEvent)> =
-        events.iter().filter(|(_, _, e)| !e.selected).collect();
-
-    sorted_events.extend_from_slice(&selected_events);
-
-    sorted_events.iter().for_each(|(step, track, grid_event)| {
-        let event_bounds = get_event_bounds(*step, *track, grid_event.offset, size);
-        let step_position = get_event_bounds(*step, *track, 0., size);
-
-        // Color definitions
-        let mut event_bg_color = match style.event.bg_color {
-            GridColor::Simple(color) => color,
-            GridColor::Multitrack(color_array) => color_array[*track],
-        };
-
-        let slider_bg_color = {
-            if highlight[*track] == *step && is_playing {
-                style.event.slider_highlighted_bg_color
-            } else {
-                style.event.slider_bg_color
+fn draw_shapes(frame: &mut Frame, shapes: &[Shape]) {
+    for shape in shapes {
+        match shape {
+            Shape::Fill(bounds, color) => {
+                frame.fill(&Path::rectangle(bounds.position(), bounds.size()), *color)
             }
-        };
-
-        let mut slider_fill_color = match slider_bg_color {
-            GridColor::Simple(color) => color,
-            GridColor::Multitrack(color_array) => color_array[*track],
-        };
-
-        // mutes
-        if mutes[*track] {
-            event_bg_color.a = 0.05;
-            slider_fill_color.a = 0.05;
-        }
-
-        if grid_event.selected {
-            // selected event contour
-            let selected_countour = Path::rectangle(
-                Point {
-                    x: event_bounds.x,
-                    y: event_bounds.y,
-                },
-                step_size,
-            );
-
-            // select must be less transparent
-            let mut contour_bg_color = style.event.contour_bg_color;
-            if mutes[*track] {
-                contour_bg_color.a = 0.15;
-                event_bg_color.a = 0.15;
-                slider_fill_color.a = 0.15;
-            }
-
-            frame.fill(&selected_countour, contour_bg_color);
-
-            // event with fill & stroke
-            let event = Path::rectangle(
-                Point {
-                    x: event_bounds.x + style.event.contour_width,
-                    y: event_bounds.y + style.event.contour_width,
-                },
-                Size {
-                    width: step_size.width - (style.event.contour_width * 2.),
-                    height: step_size.height - (style.event.contour_width * 2.),
-                },
-            );
-
-            frame.fill(&event, event_bg_color);
-
-            let slider_inner_height = step_size.height - (style.event.contour_width * 2.);
-            let velocity_height = (slider_inner_height * grid_event.velocity).ceil();
-            let velocity_top_offset = slider_inner_height - velocity_height;
-
-            // inner slider
-            let inner_slider = Path::rectangle(
-                Point {
-                    x: event_bounds.x + style.event.contour_width,
-                    y: event_bounds.y + style.event.contour_width + velocity_top_offset,
-                },
-                Size {
-                    width: step_size.width - (style.event.contour_width * 2.),
-                    height: velocity_height,
-                },
-            );
-
-            frame.fill(&inner_slider, slider_fill_color);
-            frame.stroke(
-                &event,
+            Shape::Outline(bounds, stroke) => frame.stroke(
+                &Path::rectangle(bounds.position(), bounds.size()),
                 Stroke {
-                    width: style.event.stroke.line_width,
-                    color: style.event.stroke.color,
+                    width: stroke.line_width,
+                    color: stroke.color,
                     line_cap: LineCap::Square,
                     ..Stroke::default()
                 },
-            );
-        } else {
-            // event with fill & stroke
-            let event = Path::rectangle(
-                Point {
-                    x: event_bounds.x,
-                    y: event_bounds.y,
-                },
-                step_size,
-            );
-            frame.fill(&event, event_bg_color);
-
-            let slider_inner_height = step_size.height;
-            let velocity_height = (slider_inner_height * grid_event.velocity).ceil();
-            let velocity_top_offset = slider_inner_height - velocity_height;
-
-            // inner slider
-            let inner_slider = Path::rectangle(
-                Point {
-                    x: event_bounds.x,
-                    y: event_bounds.y+ velocity_top_offset,
-                },
-                Size {
-                    width: step_size.width,
-                    height: velocity_height,
-                },
-            );
-            frame.fill(&inner_slider, slider_fill_color);
-            frame.stroke(
-                &event,
+            ),
+            Shape::VerticalLine { x, height, stroke } => frame.stroke(
+                &Path::line(Point::new(*x, 0.), Point::new(*x, *height)),
                 Stroke {
-                    width: style.event.stroke.line_width,
-                    color: style.event.stroke.color,
+                    width: stroke.line_width,
+                    color: stroke.color,
                     line_cap: LineCap::Square,
                     ..Stroke::default()
                 },
-            );
+            ),
+            Shape::Label { content, bounds, color, size } => frame.fill_text(canvas::Text {
+                content: content.clone(),
+                position: bounds.center(),
+                color: *color,
+                size: *size,
+                font: Font::Default,
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+            }),
         }
-
-        if grid_event.offset > 0. {
-            let offset = Path::rectangle(
-                Point {
-                    x: step_position.x,
-                    y: event_bounds.y + step_size.height,
-                },
-                Size {
-                    width: event_bounds.x - step_position.x - 1.,
-                    height: 2.,
-                },
-            );
-            frame.fill(&offset, style.event.positive_offset_marker_bg_color);
-        } else if grid_event.offset < 0. {
-            let offset = Path::rectangle(
-                Point {
-                    x: event_bounds.x,
-                    y: event_bounds.y + step_size.height,
-                },
-                Size {
-                    width: step_position.x - event_bounds.x,
-                    height: 2.,
-                },
-            );
-            frame.fill(&offset, style.event.negative_offset_marker_bg_color);
-        }
-    });
-}
\ No newline at end of file
+    }
+}
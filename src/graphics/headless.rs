@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use iced_graphics::{Backend, Primitive};
+use iced_native::{
+    layout, renderer, Background, Color, Layout, Point, Rectangle, Size, Vector, Widget,
+};
+
+/// A backend drawing nothing, so the grid renderer can run without a GPU.
+///
+/// The primitives are still built, canvas geometry included, and can be
+/// read back with `Renderer::with_primitives` or [`draw`].
+#[derive(Debug, Default)]
+pub struct NullBackend;
+
+impl Backend for NullBackend {}
+
+pub type Renderer = iced_graphics::Renderer<NullBackend>;
+
+pub fn renderer() -> Renderer {
+    Renderer::new(NullBackend)
+}
+
+/// Lays out a widget at the given size and returns the primitives it draws.
+pub fn draw<Message>(
+    widget: &dyn Widget<Message, Renderer>,
+    size: Size,
+    cursor_position: Point,
+) -> Vec<Primitive> {
+    let mut renderer = renderer();
+    let node = widget.layout(&renderer, &layout::Limits::new(Size::ZERO, size));
+
+    widget.draw(
+        &mut renderer,
+        &renderer::Style::default(),
+        Layout::new(&node),
+        cursor_position,
+        &Rectangle::with_size(size),
+    );
+
+    renderer.with_primitives(|_, primitives| primitives.to_vec())
+}
+
+/// Describes primitives one line per quad, text, layer or mesh color, in
+/// window coordinates, so drawings can be compared as text.
+///
+/// Meshes are reduced to the bounds covered by each of their colors, which
+/// keeps the description independent from the tessellation.
+pub fn describe(primitives: &[Primitive]) -> String {
+    let mut lines = vec![];
+
+    primitives
+        .iter()
+        .for_each(|primitive| describe_primitive(primitive, Vector::new(0., 0.), &mut lines));
+
+    lines.join("\n")
+}
+
+pub fn format_color(color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+
+    format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+}
+
+fn format_rectangle(bounds: Rectangle) -> String {
+    format!(
+        "({:.1}, {:.1}, {:.1}, {:.1})",
+        bounds.x, bounds.y, bounds.width, bounds.height
+    )
+}
+
+fn describe_primitive(primitive: &Primitive, translation: Vector, lines: &mut Vec<String>) {
+    match primitive {
+        Primitive::None => {}
+        Primitive::Group { primitives } => primitives
+            .iter()
+            .for_each(|primitive| describe_primitive(primitive, translation, lines)),
+        Primitive::Translate {
+            translation: primitive_translation,
+            content,
+        } => describe_primitive(content, translation + *primitive_translation, lines),
+        Primitive::Cached { cache } => describe_primitive(cache, translation, lines),
+        Primitive::Clip {
+            bounds,
+            offset,
+            content,
+        } => {
+            lines.push(format!("layer {}", format_rectangle(*bounds + translation)));
+
+            let offset = Vector::new(offset.x as f32, offset.y as f32);
+            describe_primitive(content, translation - offset, lines);
+        }
+        Primitive::Quad {
+            bounds,
+            background,
+            border_width,
+            border_color,
+            ..
+        } => {
+            let background = match background {
+                Background::Color(color) => format_color(*color),
+            };
+
+            lines.push(format!(
+                "quad {} {} border {:.1} {}",
+                format_rectangle(*bounds + translation),
+                background,
+                border_width,
+                format_color(*border_color)
+            ));
+        }
+        Primitive::Text {
+            content,
+            bounds,
+            color,
+            size,
+            ..
+        } => lines.push(format!(
+            "text {:?} {} {} {:.1}",
+            content,
+            format_rectangle(*bounds + translation),
+            format_color(*color),
+            size
+        )),
+        Primitive::Mesh2D { buffers, .. } => {
+            // color => (top left, bottom right)
+            let mut color_bounds: BTreeMap<String, (Point, Point)> = BTreeMap::new();
+
+            for vertex in buffers.vertices.iter() {
+                let position = Point::new(vertex.position[0], vertex.position[1]) + translation;
+                let entry = color_bounds
+                    .entry(format_color(Color::from(vertex.color)))
+                    .or_insert((position, position));
+
+                entry.0 = Point::new(entry.0.x.min(position.x), entry.0.y.min(position.y));
+                entry.1 = Point::new(entry.1.x.max(position.x), entry.1.y.max(position.y));
+            }
+
+            for (color, (top_left, bottom_right)) in color_bounds {
+                let bounds = Rectangle::new(top_left, Size::new(
+                    bottom_right.x - top_left.x,
+                    bottom_right.y - top_left.y,
+                ));
+
+                lines.push(format!("mesh {} {}", color, format_rectangle(bounds)));
+            }
+        }
+        Primitive::Image { bounds, .. } => {
+            lines.push(format!("image {}", format_rectangle(*bounds + translation)))
+        }
+        Primitive::Svg { bounds, .. } => {
+            lines.push(format!("svg {}", format_rectangle(*bounds + translation)))
+        }
+    }
+}
@@ -8,11 +8,15 @@ pub use native::h_list;
 pub use native::grid;
 pub use native::snapshot_view;
 pub use native::multi_slider;
+pub use native::ruler;
+pub use native::track_header;
 
 pub use grid::*;
 pub use h_list::*;
 pub use snapshot_view::*;
 pub use multi_slider::*;
+pub use ruler::*;
+pub use track_header::*;
 
 // #[cfg(not(target_arch = "wasm32"))]
 // mod platform {
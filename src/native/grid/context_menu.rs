@@ -0,0 +1,194 @@
+use iced_native::{
+    event, keyboard, layout, mouse, overlay, renderer, Clipboard,
+    Event, Layout, Point, Rectangle, Shell, Size,
+};
+
+use crate::core::grid::GridMessage;
+
+pub const MENU_WIDTH: f32 = 150.;
+pub const MENU_ITEM_HEIGHT: f32 = 22.;
+pub const MENU_PADDING: f32 = 4.;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuAction {
+    Delete,
+    Duplicate,
+    SelectTrack,
+    ClearTrack,
+    Quantize,
+    SetVelocity(f32),
+}
+
+impl MenuAction {
+    pub const ALL: [MenuAction; 9] = [
+        MenuAction::Delete,
+        MenuAction::Duplicate,
+        MenuAction::SelectTrack,
+        MenuAction::ClearTrack,
+        MenuAction::Quantize,
+        MenuAction::SetVelocity(1.),
+        MenuAction::SetVelocity(0.75),
+        MenuAction::SetVelocity(0.5),
+        MenuAction::SetVelocity(0.25),
+    ];
+
+    pub fn label(&self) -> String {
+        match self {
+            MenuAction::Delete => String::from("Delete"),
+            MenuAction::Duplicate => String::from("Duplicate"),
+            MenuAction::SelectTrack => String::from("Select track"),
+            MenuAction::ClearTrack => String::from("Clear track"),
+            MenuAction::Quantize => String::from("Quantize"),
+            MenuAction::SetVelocity(velocity) => {
+                format!("Velocity {}%", (velocity * 100.).round())
+            }
+        }
+    }
+
+    // track is the grid track the menu was opened on
+    pub fn messages(&self, track: usize) -> Vec<GridMessage> {
+        match self {
+            MenuAction::Delete => vec![GridMessage::DeleteSelection()],
+            MenuAction::Duplicate => vec![GridMessage::DuplicateSelection()],
+            MenuAction::SelectTrack => vec![GridMessage::SelectTrack(track)],
+            MenuAction::ClearTrack => vec![GridMessage::ClearTrack(track)],
+            MenuAction::Quantize => vec![GridMessage::QuantizeSelection()],
+            MenuAction::SetVelocity(velocity) => {
+                vec![GridMessage::SetSelectionVelocity(*velocity)]
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ContextMenu {
+    pub position: Point,
+    pub track: usize,
+}
+
+pub fn get_menu_size() -> Size {
+    Size {
+        width: MENU_WIDTH,
+        height: MenuAction::ALL.len() as f32 * MENU_ITEM_HEIGHT + 2. * MENU_PADDING,
+    }
+}
+
+pub fn get_item_bounds(bounds: Rectangle, index: usize) -> Rectangle {
+    Rectangle {
+        x: bounds.x,
+        y: bounds.y + MENU_PADDING + index as f32 * MENU_ITEM_HEIGHT,
+        width: bounds.width,
+        height: MENU_ITEM_HEIGHT,
+    }
+}
+
+pub fn get_hovered_item(bounds: Rectangle, cursor: Point) -> Option<usize> {
+    (0..MenuAction::ALL.len()).find(|index| get_item_bounds(bounds, *index).contains(cursor))
+}
+
+pub struct ContextMenuOverlay<'a, Message, Renderer: super::Renderer> {
+    menu: &'a mut Option<ContextMenu>,
+    on_event: &'a dyn Fn(GridMessage) -> Message,
+    style: &'a Renderer::Style,
+}
+
+impl<'a, Message, Renderer: super::Renderer> ContextMenuOverlay<'a, Message, Renderer> {
+    pub fn new(
+        menu: &'a mut Option<ContextMenu>,
+        on_event: &'a dyn Fn(GridMessage) -> Message,
+        style: &'a Renderer::Style,
+    ) -> Self {
+        ContextMenuOverlay {
+            menu,
+            on_event,
+            style,
+        }
+    }
+}
+
+impl<'a, Message, Renderer> overlay::Overlay<Message, Renderer>
+    for ContextMenuOverlay<'a, Message, Renderer>
+where
+    Renderer: super::Renderer,
+{
+    fn layout(&self, _renderer: &Renderer, bounds: Size, position: Point) -> layout::Node {
+        let size = get_menu_size();
+
+        // keep the menu inside the window
+        let x = position.x.min(bounds.width - size.width).max(0.);
+        let y = position.y.min(bounds.height - size.height).max(0.);
+
+        let mut node = layout::Node::new(size);
+        node.move_to(Point::new(x, y));
+        node
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) {
+        let bounds = layout.bounds();
+
+        renderer.draw_context_menu(
+            bounds,
+            &MenuAction::ALL,
+            get_hovered_item(bounds, cursor_position),
+            self.style,
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(_)) => {
+                let bounds = layout.bounds();
+
+                if let Some(menu) = self.menu.take() {
+                    if bounds.contains(cursor_position) {
+                        if let Some(index) = get_hovered_item(bounds, cursor_position) {
+                            MenuAction::ALL[index]
+                                .messages(menu.track)
+                                .into_iter()
+                                .for_each(|message| shell.publish((self.on_event)(message)));
+                        }
+
+                        return event::Status::Captured;
+                    }
+                }
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code: keyboard::KeyCode::Escape,
+                ..
+            }) => {
+                *self.menu = None;
+                return event::Status::Captured;
+            }
+            _ => {}
+        }
+
+        event::Status::Ignored
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        match get_hovered_item(layout.bounds(), cursor_position) {
+            Some(_) => mouse::Interaction::Pointer,
+            None => mouse::Interaction::default(),
+        }
+    }
+}
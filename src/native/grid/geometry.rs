@@ -0,0 +1,471 @@
+//! The shapes of the grid, shared by its renderers.
+//!
+//! Every function lists the rectangles, lines and labels of a part of the
+//! grid in content coordinates, with their colors. `quad` draws them as quads
+//! and text, `graphics::grid` as canvas paths in its cached layers.
+use std::time::Duration;
+
+use iced_native::{Color, Point, Rectangle, Size};
+
+use ganic_no_std::{NUM_PERCS, NUM_STEPS};
+use crate::core::grid::{
+    get_event_content_bounds, get_step_width, GridEvent, GridPattern, Snap, BEATS,
+    OFFSET_THRESHOLD, STEPS_PER_BEAT, TRACK_MARGIN_BOTTOM,
+};
+use crate::style::color_utils::mix;
+use crate::style::grid::{Flash, GridColor, Stroke, Style, VelocityDisplay};
+
+pub const READOUT_SIZE: Size = Size::new(44., 20.);
+pub const READOUT_MARGIN: f32 = 8.;
+pub const MIN_EVENT_HEIGHT: f32 = 2.;
+
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Fill(Rectangle, Color),
+    Outline(Rectangle, Stroke),
+    VerticalLine { x: f32, height: f32, stroke: Stroke }, // from the top, centered on x
+    Label { content: String, bounds: Rectangle, color: Color, size: f32 }, // centered
+}
+
+/// Where the velocity readout is drawn, next to the cursor.
+pub fn get_readout_bounds(cursor_position: Point) -> Rectangle {
+    Rectangle {
+        x: cursor_position.x + READOUT_MARGIN,
+        y: cursor_position.y - READOUT_MARGIN - READOUT_SIZE.height,
+        width: READOUT_SIZE.width,
+        height: READOUT_SIZE.height,
+    }
+}
+
+pub fn grid(
+    size: Size,
+    step_size: Size,
+    snap: Snap,
+    focused_track: Option<usize>,
+    style: &Style,
+) -> Vec<Shape> {
+    let mut shapes = vec![];
+    let beat_width = step_size.width * STEPS_PER_BEAT as f32;
+
+    for beat in 0..BEATS {
+        let bg_color = match beat {
+            0 | 2 => style.grid.even_beat_bg_color,
+            _ => style.grid.odd_beat_bg_color,
+        };
+
+        shapes.push(Shape::Fill(
+            Rectangle::new(
+                Point::new(beat as f32 * beat_width + step_size.width, 0.),
+                Size::new(beat_width, size.height),
+            ),
+            bg_color,
+        ));
+    }
+
+    // edges bg
+    for edge_step in [0, NUM_STEPS + 1] {
+        shapes.push(Shape::Fill(
+            Rectangle::new(
+                Point::new(edge_step as f32 * step_size.width, 0.),
+                Size::new(step_size.width, size.height),
+            ),
+            style.grid.edge_step_bg_color,
+        ));
+    }
+
+    // focused track, over the beats so they still show through
+    if let Some(track) = focused_track {
+        shapes.push(Shape::Fill(
+            Rectangle::new(
+                Point::new(0., track as f32 * (step_size.height + TRACK_MARGIN_BOTTOM)),
+                Size::new(size.width, step_size.height),
+            ),
+            style.selected_track_bg_color,
+        ));
+    }
+
+    // track margins
+    for track in 0..NUM_PERCS {
+        shapes.push(Shape::Fill(
+            Rectangle::new(
+                Point::new(
+                    0.,
+                    track as f32 * (step_size.height + TRACK_MARGIN_BOTTOM) + step_size.height,
+                ),
+                Size::new(size.width, TRACK_MARGIN_BOTTOM),
+            ),
+            style.grid.track_margin_color,
+        ));
+    }
+
+    let height = size.height - TRACK_MARGIN_BOTTOM;
+
+    // faint lines on the snap positions inside each step
+    if let (Some(stroke), Some(snap_size)) = (style.grid.sub_step_line, snap.get_size()) {
+        for step in 0..NUM_STEPS {
+            let mut position = snap_size;
+
+            while position < 1. - OFFSET_THRESHOLD {
+                let x = (step as f32 + 1. + position) * step_size.width;
+                shapes.push(Shape::VerticalLine { x, height, stroke });
+
+                position += snap_size;
+            }
+        }
+    }
+
+    for step in 0..=NUM_STEPS {
+        let stroke = match step {
+            0 | NUM_STEPS => style.grid.edge_step_line,
+            step_index if (step_index / STEPS_PER_BEAT) % 2 == 1 => style.grid.odd_beat_line,
+            _ => style.grid.even_beat_line,
+        };
+
+        let x = (step + 1) as f32 * step_size.width;
+        shapes.push(Shape::VerticalLine { x, height, stroke });
+    }
+
+    shapes
+}
+
+/// The steps being played, under their events.
+pub fn highlight(
+    size: Size,
+    highlight: [usize; NUM_PERCS],
+    mutes: [bool; NUM_PERCS],
+    style: &Style,
+) -> Vec<Shape> {
+    highlight
+        .iter()
+        .enumerate()
+        .filter(|(track, _)| !mutes[*track])
+        .map(|(track, highlighted_step)| {
+            let step_bounds = get_event_content_bounds(*highlighted_step, track, 0., size);
+
+            Shape::Fill(step_bounds, style.current_step_bg_color)
+        })
+        .collect()
+}
+
+pub fn playhead(size: Size, position: f32, stroke: Stroke) -> Shape {
+    Shape::VerticalLine {
+        x: (1. + position) * get_step_width(size),
+        height: size.height,
+        stroke,
+    }
+}
+
+/// The played events, brighter and larger as they were just hit.
+pub fn flashes(
+    size: Size,
+    grid_pattern: &GridPattern,
+    step_size: Size,
+    flashes: [Option<(usize, Duration)>; NUM_PERCS],
+    mutes: [bool; NUM_PERCS],
+    flash: Flash,
+) -> Vec<Shape> {
+    let mut shapes = vec![];
+
+    for (track, (step, elapsed)) in flashes
+        .iter()
+        .enumerate()
+        .filter_map(|(track, hit)| hit.map(|hit| (track, hit)))
+    {
+        let grid_event = match (mutes[track], grid_pattern.data.get(&(step, track))) {
+            (false, Some(grid_event)) => grid_event,
+            _ => continue,
+        };
+
+        let intensity = flash.intensity(elapsed, grid_event.velocity);
+        if intensity <= 0. {
+            continue;
+        }
+
+        let event_bounds = get_event_content_bounds(step, track, grid_event.offset, size);
+        let spread = flash.spread * intensity;
+
+        shapes.push(Shape::Fill(
+            Rectangle {
+                x: event_bounds.x - spread,
+                y: event_bounds.y - spread,
+                width: step_size.width + 2. * spread,
+                height: step_size.height + 2. * spread,
+            },
+            Color {
+                a: flash.color.a * intensity,
+                ..flash.color
+            },
+        ));
+    }
+
+    shapes
+}
+
+/// The original positions of a moved selection.
+pub fn ghosts(size: Size, ghost_pattern: &GridPattern, step_size: Size, stroke: Stroke) -> Vec<Shape> {
+    ghost_pattern
+        .data
+        .iter()
+        .filter(|(_, grid_event)| grid_event.selected)
+        .map(|((step, track), grid_event)| {
+            let event_bounds = get_event_content_bounds(*step, *track, grid_event.offset, size);
+
+            Shape::Outline(Rectangle::new(event_bounds.position(), step_size), stroke)
+        })
+        .collect()
+}
+
+pub fn selection(selection: Rectangle, is_deselecting: bool, style: &Style) -> Vec<Shape> {
+    // a deselection is filled so it can't be mistaken for an additive one
+    match is_deselecting {
+        true => vec![
+            Shape::Fill(selection, style.deselection_bg_color),
+            Shape::Outline(selection, style.deselection_stroke),
+        ],
+        false => vec![Shape::Outline(selection, style.selection_stroke)],
+    }
+}
+
+/// The events of `step`, or of every step, but the played ones.
+pub fn steps(
+    size: Size,
+    grid_pattern: &GridPattern,
+    step: Option<usize>,
+    step_size: Size,
+    played: Option<[usize; NUM_PERCS]>,
+    mutes: [bool; NUM_PERCS],
+    style: &Style,
+) -> Vec<Shape> {
+    let mut shapes = vec![];
+    let mut events: Vec<(usize, usize, GridEvent)> = grid_pattern
+        .data
+        .iter()
+        .filter(|((event_step, _), _)| step.map_or(true, |step| *event_step == step))
+        .filter(|((step, track), _)| played.map_or(true, |highlight| highlight[*track] != *step))
+        .map(|((step, track), grid_event)| (*step, *track, *grid_event))
+        .collect();
+
+    // by track then step, selected events on top
+    events.sort_by_key(|(step, track, grid_event)| (grid_event.selected, *track, *step));
+
+    events.into_iter().for_each(|event| {
+        push_event(
+            &mut shapes,
+            size,
+            step_size,
+            event,
+            style.event.slider_bg_color,
+            false,
+            mutes,
+            style,
+        );
+    });
+
+    shapes
+}
+
+/// The events under the playhead, drawn over the other ones.
+pub fn played_steps(
+    size: Size,
+    grid_pattern: &GridPattern,
+    step_size: Size,
+    highlight: [usize; NUM_PERCS],
+    mutes: [bool; NUM_PERCS],
+    style: &Style,
+) -> Vec<Shape> {
+    let mut shapes = vec![];
+
+    for (track, step) in highlight.iter().enumerate() {
+        if let Some(grid_event) = grid_pattern.data.get(&(*step, track)) {
+            push_event(
+                &mut shapes,
+                size,
+                step_size,
+                (*step, track, *grid_event),
+                style.event.slider_highlighted_bg_color,
+                true,
+                mutes,
+                style,
+            );
+        }
+    }
+
+    shapes
+}
+
+fn push_event(
+    shapes: &mut Vec<Shape>,
+    size: Size,
+    step_size: Size,
+    (step, track, grid_event): (usize, usize, GridEvent),
+    slider_bg_color: GridColor,
+    is_played: bool,
+    mutes: [bool; NUM_PERCS],
+    style: &Style,
+) {
+    let event_bounds = get_event_content_bounds(step, track, grid_event.offset, size);
+    let step_position = get_event_content_bounds(step, track, 0., size);
+
+    let mut event_bg_color = match style.event.bg_color {
+        GridColor::Simple(color) => color,
+        GridColor::Multitrack(color_array) => color_array[track],
+    };
+
+    let mut slider_fill_color = match slider_bg_color {
+        GridColor::Simple(color) => color,
+        GridColor::Multitrack(color_array) => color_array[track],
+    };
+
+    if mutes[track] {
+        event_bg_color.a = 0.05;
+        slider_fill_color.a = 0.05;
+    }
+
+    let body_bounds = if grid_event.selected {
+        // select must be less transparent
+        let mut contour_bg_color = style.event.contour_bg_color;
+        if mutes[track] {
+            contour_bg_color.a = 0.15;
+            event_bg_color.a = 0.15;
+            slider_fill_color.a = 0.15;
+        }
+
+        shapes.push(Shape::Fill(
+            Rectangle::new(event_bounds.position(), step_size),
+            contour_bg_color,
+        ));
+
+        Rectangle {
+            x: event_bounds.x + style.event.contour_width,
+            y: event_bounds.y + style.event.contour_width,
+            width: step_size.width - (style.event.contour_width * 2.),
+            height: step_size.height - (style.event.contour_width * 2.),
+        }
+    } else {
+        Rectangle::new(event_bounds.position(), step_size)
+    };
+
+    push_event_body(
+        shapes,
+        body_bounds,
+        grid_event.velocity,
+        event_bg_color,
+        slider_fill_color,
+        is_played,
+        style,
+    );
+
+    if grid_event.offset > 0. {
+        shapes.push(Shape::Fill(
+            Rectangle {
+                x: step_position.x,
+                y: event_bounds.y + step_size.height,
+                width: event_bounds.x - step_position.x - 1.,
+                height: 2.,
+            },
+            style.event.positive_offset_marker_bg_color,
+        ));
+    } else if grid_event.offset < 0. {
+        shapes.push(Shape::Fill(
+            Rectangle {
+                x: event_bounds.x,
+                y: event_bounds.y + step_size.height,
+                width: step_position.x - event_bounds.x,
+                height: 2.,
+            },
+            style.event.negative_offset_marker_bg_color,
+        ));
+    }
+}
+
+// the event fill and its velocity, as set by the style
+fn push_event_body(
+    shapes: &mut Vec<Shape>,
+    bounds: Rectangle,
+    velocity: f32,
+    event_bg_color: Color,
+    slider_fill_color: Color,
+    is_played: bool,
+    style: &Style,
+) {
+    let outline = match style.event.velocity_display {
+        VelocityDisplay::Bar => {
+            let velocity_height = (bounds.height * velocity).ceil();
+
+            shapes.push(Shape::Fill(bounds, event_bg_color));
+            shapes.push(Shape::Fill(
+                Rectangle {
+                    y: bounds.y + bounds.height - velocity_height,
+                    height: velocity_height,
+                    ..bounds
+                },
+                slider_fill_color,
+            ));
+
+            bounds
+        }
+        VelocityDisplay::Opacity => {
+            shapes.push(Shape::Fill(bounds, event_bg_color));
+            shapes.push(Shape::Fill(
+                bounds,
+                Color {
+                    a: slider_fill_color.a * velocity,
+                    ..slider_fill_color
+                },
+            ));
+
+            bounds
+        }
+        // without a slider, the played events are filled with the highlighted slider color
+        VelocityDisplay::Gradient(_, _) if is_played => {
+            shapes.push(Shape::Fill(bounds, slider_fill_color));
+
+            bounds
+        }
+        VelocityDisplay::Gradient(low, high) => {
+            shapes.push(Shape::Fill(
+                bounds,
+                Color {
+                    a: slider_fill_color.a,
+                    ..mix(low, high, velocity)
+                },
+            ));
+
+            bounds
+        }
+        VelocityDisplay::Height => {
+            // kept visible at the lowest velocities, and whole while played
+            let height = match is_played {
+                true => bounds.height,
+                false => (bounds.height * velocity).ceil().max(MIN_EVENT_HEIGHT.min(bounds.height)),
+            };
+            let scaled_bounds = Rectangle {
+                y: bounds.y + bounds.height - height,
+                height,
+                ..bounds
+            };
+
+            shapes.push(Shape::Fill(scaled_bounds, slider_fill_color));
+
+            scaled_bounds
+        }
+        VelocityDisplay::Label { color, size } => {
+            let bg_color = if is_played { slider_fill_color } else { event_bg_color };
+
+            shapes.push(Shape::Fill(bounds, bg_color));
+            shapes.push(Shape::Label {
+                content: format!("{}", (velocity * 127.).round()),
+                bounds,
+                color: Color {
+                    a: color.a * slider_fill_color.a,
+                    ..color
+                },
+                size,
+            });
+
+            bounds
+        }
+    };
+
+    shapes.push(Shape::Outline(outline, style.event.stroke));
+}
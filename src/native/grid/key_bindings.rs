@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use iced_native::keyboard::{KeyCode, Modifiers};
+
+use crate::core::grid::{GridMessage, Snap, OFFSET_THRESHOLD};
+
+/// An operation the [`Grid`] can perform from the keyboard.
+///
+/// [`Grid`]: super::Grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridAction {
+    SelectAll,
+    DeleteSelection,
+    MoveSelection((f32, isize)), // (step offset, track offset)
+    NudgeSelection(isize), // to the next snap position in this direction
+    Cancel, // abort the current drag and restore the pattern
+}
+
+impl GridAction {
+    pub fn messages(&self, snap: Snap) -> Vec<GridMessage> {
+        match self {
+            GridAction::SelectAll => vec![GridMessage::EmptySelection(), GridMessage::SelectAll()],
+            GridAction::DeleteSelection => vec![GridMessage::DeleteSelection()],
+            GridAction::MoveSelection(movement) => vec![GridMessage::MoveSelection(*movement)],
+            GridAction::NudgeSelection(direction) => {
+                vec![GridMessage::NudgeSelection(*direction, snap)]
+            }
+            GridAction::Cancel => vec![GridMessage::DiscardState()],
+        }
+    }
+}
+
+/// Maps a key and its exact modifiers, or a key alone, to a [`GridAction`].
+///
+/// The default bindings are the historical ones: `A` selects everything,
+/// `Backspace` deletes the selection and the arrows nudge it by a whole step,
+/// to the next snap position when Ctrl/Cmd is held or by a small offset when
+/// Alt is held. `Escape` cancels the current drag whatever the modifiers are.
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    bindings: HashMap<(KeyCode, Modifiers), GridAction>,
+    any_modifiers: HashMap<KeyCode, GridAction>, // used when no exact binding matches
+}
+
+impl KeyBindings {
+    /// Creates an empty set of bindings.
+    pub fn new() -> Self {
+        KeyBindings {
+            bindings: HashMap::new(),
+            any_modifiers: HashMap::new(),
+        }
+    }
+
+    pub fn bind(mut self, key_code: KeyCode, modifiers: Modifiers, action: GridAction) -> Self {
+        self.bindings.insert((key_code, modifiers), action);
+        self
+    }
+
+    pub fn unbind(mut self, key_code: KeyCode, modifiers: Modifiers) -> Self {
+        self.bindings.remove(&(key_code, modifiers));
+        self
+    }
+
+    /// Binds a key whatever modifiers are held. Exact bindings take precedence.
+    pub fn bind_any(mut self, key_code: KeyCode, action: GridAction) -> Self {
+        self.any_modifiers.insert(key_code, action);
+        self
+    }
+
+    pub fn unbind_any(mut self, key_code: KeyCode) -> Self {
+        self.any_modifiers.remove(&key_code);
+        self
+    }
+
+    pub fn get(&self, key_code: KeyCode, modifiers: Modifiers) -> Option<GridAction> {
+        self.bindings
+            .get(&(key_code, modifiers))
+            .or_else(|| self.any_modifiers.get(&key_code))
+            .copied()
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let mut key_bindings = KeyBindings::new()
+            .bind(KeyCode::A, Modifiers::empty(), GridAction::SelectAll)
+            .bind(KeyCode::Backspace, Modifiers::empty(), GridAction::DeleteSelection)
+            .bind(KeyCode::Left, Modifiers::empty(), GridAction::MoveSelection((-1., 0)))
+            .bind(KeyCode::Up, Modifiers::empty(), GridAction::MoveSelection((0., -1)))
+            .bind(KeyCode::Right, Modifiers::empty(), GridAction::MoveSelection((1., 0)))
+            .bind(KeyCode::Down, Modifiers::empty(), GridAction::MoveSelection((0., 1)));
+
+        // Cmd on mac, Ctrl everywhere else
+        for modifiers in [Modifiers::LOGO, Modifiers::CTRL] {
+            key_bindings = key_bindings
+                .bind(KeyCode::A, modifiers, GridAction::SelectAll)
+                .bind(KeyCode::Backspace, modifiers, GridAction::DeleteSelection)
+                .bind(KeyCode::Left, modifiers, GridAction::NudgeSelection(-1))
+                .bind(KeyCode::Up, modifiers, GridAction::MoveSelection((0., -1)))
+                .bind(KeyCode::Right, modifiers, GridAction::NudgeSelection(1))
+                .bind(KeyCode::Down, modifiers, GridAction::MoveSelection((0., 1)));
+        }
+
+        // fine nudge, whatever the snap
+        key_bindings = key_bindings
+            .bind(KeyCode::Left, Modifiers::ALT, GridAction::MoveSelection((-OFFSET_THRESHOLD, 0)))
+            .bind(KeyCode::Right, Modifiers::ALT, GridAction::MoveSelection((OFFSET_THRESHOLD, 0)));
+
+        // modifiers are often held while dragging
+        key_bindings.bind_any(KeyCode::Escape, GridAction::Cancel)
+    }
+}
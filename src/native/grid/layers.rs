@@ -0,0 +1,117 @@
+//! What a renderer has to redraw, for the ones caching parts of the grid.
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+use ganic_no_std::NUM_STEPS;
+
+/// The cached layers of a grid, each with a generation raised whenever the
+/// layer changed: the grid itself, the events of each step but the played
+/// ones, and the played steps.
+///
+/// Renderers keep their caches in [`Layers::with_storage`], between frames,
+/// and redraw a layer when its generation differs from the cached one.
+pub struct Layers {
+    grid: Cell<u64>,
+    steps: Vec<Cell<u64>>, // one per step
+    highlight: Cell<u64>,
+    style: RefCell<Option<Box<dyn Any + Send>>>, // the one last drawn
+    storage: RefCell<Option<Box<dyn Any + Send>>>,
+}
+
+impl Layers {
+    pub(crate) fn new() -> Self {
+        Layers {
+            grid: Cell::new(0),
+            steps: (0..NUM_STEPS).map(|_| Cell::new(0)).collect(),
+            highlight: Cell::new(0),
+            style: RefCell::new(None),
+            storage: RefCell::new(None),
+        }
+    }
+
+    pub(crate) fn clear_grid(&self) {
+        raise(&self.grid);
+    }
+
+    pub(crate) fn clear_step(&self, step: usize) {
+        if let Some(generation) = self.steps.get(step) {
+            raise(generation);
+        }
+    }
+
+    // the played events are drawn from the pattern too
+    pub(crate) fn clear_events(&self) {
+        self.steps.iter().for_each(raise);
+        self.clear_highlight();
+    }
+
+    pub(crate) fn clear_highlight(&self) {
+        raise(&self.highlight);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.clear_grid();
+        self.clear_events();
+    }
+
+    pub fn grid(&self) -> u64 {
+        self.grid.get()
+    }
+
+    pub fn step(&self, step: usize) -> u64 {
+        self.steps.get(step).map_or(0, Cell::get)
+    }
+
+    pub fn highlight(&self) -> u64 {
+        self.highlight.get()
+    }
+
+    /// Redraws every layer if `style` differs from the one they were drawn
+    /// with, e.g. on hover when the hovered style isn't the default one.
+    pub fn restyle<S>(&self, style: &S)
+    where
+        S: PartialEq + Clone + Send + 'static,
+    {
+        let mut drawn_style = self.style.borrow_mut();
+
+        let is_same = drawn_style
+            .as_deref()
+            .and_then(|drawn_style| drawn_style.downcast_ref::<S>())
+            .map_or(false, |drawn_style| drawn_style == style);
+
+        if !is_same {
+            self.clear();
+            *drawn_style = Some(Box::new(style.clone()));
+        }
+    }
+
+    /// Runs `f` on the storage of the renderer, created on first use or when
+    /// the grid is drawn by a renderer storing another type.
+    pub fn with_storage<T, R>(&self, f: impl FnOnce(&mut T) -> R) -> R
+    where
+        T: Default + Send + 'static,
+    {
+        let mut storage = self.storage.borrow_mut();
+
+        if !storage.as_deref().map_or(false, |storage| storage.is::<T>()) {
+            *storage = Some(Box::new(T::default()));
+        }
+
+        f(storage.as_deref_mut().and_then(|storage| storage.downcast_mut::<T>()).unwrap())
+    }
+}
+
+fn raise(generation: &Cell<u64>) {
+    generation.set(generation.get().wrapping_add(1));
+}
+
+impl fmt::Debug for Layers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Layers")
+            .field("grid", &self.grid)
+            .field("steps", &self.steps)
+            .field("highlight", &self.highlight)
+            .finish()
+    }
+}
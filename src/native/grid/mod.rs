@@ -1,22 +1,54 @@
+use std::cell::Cell;
 use std::fmt::Debug;
+use std::time::{Duration, Instant};
 use iced_native::{
-    event, keyboard, layout, mouse, Clipboard,
+    event, keyboard, layout, mouse, overlay, Clipboard,
     Element, Event, Layout, Length, Padding,
     Point, Rectangle, Size, Widget, Shell,
 };
-use iced_graphics::canvas;
 
 use ganic_no_std::{NUM_PERCS, NUM_STEPS};
-use crate::core::grid::{GridMessage, GridPattern};
+use crate::core::grid::{
+    get_hovered_step, get_hovered_track, get_step_width, DragMessage, DragPayload, GridMessage,
+    GridPattern, Snap, Viewport,
+};
 pub use crate::style::multi_slider::{Style, StyleSheet};
 
+pub mod context_menu;
+pub mod geometry;
+pub mod key_bindings;
+pub mod layers;
 pub mod modes;
+pub mod quad;
+pub mod tooltip;
+pub use key_bindings::{GridAction, KeyBindings};
+pub use layers::Layers;
+use context_menu::{ContextMenu, ContextMenuOverlay, MenuAction};
+use tooltip::{Tooltip, TooltipOverlay, TOOLTIP_DELAY};
 use modes::{Idle, Transition, WidgetState};
 
+/// The [`StyleSheet`] style a [`Grid`] is drawn with, from the most to the least important.
+///
+/// [`StyleSheet`]: crate::style::grid::StyleSheet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Disabled,
+    Dragging, // a selection is being moved
+    Focused,
+    Hovered,
+    Active,
+}
+
 pub struct Grid<'a, Message, Renderer: self::Renderer> {
     state: &'a mut State,
     live_pattern: GridPattern,
     on_event: Box<dyn Fn(GridMessage) -> Message>,
+    on_drag: Option<Box<dyn Fn(DragMessage) -> Message + 'a>>,
+    drag_payload: Option<DragPayload>,
+    tooltip_bpm: Option<f32>,
+    tooltip_delay: Duration,
+    is_disabled: bool,
+    key_bindings: KeyBindings,
     width: Length,
     height: Length,
     padding: Padding,
@@ -38,6 +70,12 @@ impl<'a, Message, Renderer: self::Renderer> Grid<'a, Message, Renderer> {
             state,
             live_pattern,
             on_event: Box::new(on_event),
+            on_drag: None,
+            drag_payload: None,
+            tooltip_bpm: None,
+            tooltip_delay: TOOLTIP_DELAY,
+            is_disabled: false,
+            key_bindings: KeyBindings::default(),
             width,
             height,
             padding: Padding::ZERO,
@@ -60,10 +98,83 @@ impl<'a, Message, Renderer: self::Renderer> Grid<'a, Message, Renderer> {
         self
     }
 
+    pub fn key_bindings(mut self, key_bindings: KeyBindings) -> Self {
+        self.key_bindings = key_bindings;
+        self
+    }
+
+    /// Sets the positions quantized drags and [`GridAction::NudgeSelection`] snap to,
+    /// bound to Ctrl/Cmd+Left/Right by default.
+    pub fn snap(mut self, snap: Snap) -> Self {
+        if self.state.context.snap != snap {
+            // sub-step lines are part of the grid
+            self.state.layers.clear_grid();
+            self.state.context.snap = snap;
+        }
+
+        self
+    }
+
+    /// Lets the selection be dragged out of the grid, and other payloads be dropped on it.
+    pub fn on_drag<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(DragMessage) -> Message,
+    {
+        self.on_drag = Some(Box::new(f));
+        self
+    }
+
+    /// The payload being dragged between widgets, if any.
+    pub fn drag_payload(mut self, payload: Option<DragPayload>) -> Self {
+        self.drag_payload = payload;
+        self
+    }
+
+    /// Shows the values of the hovered event after a delay, offsets are also
+    /// given in milliseconds at this tempo.
+    ///
+    /// The tooltip follows the grabbed event without delay during velocity and
+    /// move drags. Otherwise it appears on the first redraw after the delay,
+    /// which the host requests at [`State::tooltip_deadline`].
+    pub fn tooltip(mut self, bpm: f32) -> Self {
+        self.tooltip_bpm = Some(bpm);
+        self
+    }
+
+    pub fn tooltip_delay(mut self, delay: Duration) -> Self {
+        self.tooltip_delay = delay;
+        self
+    }
+
+    /// A disabled grid ignores every event and is drawn with the disabled style.
+    pub fn disabled(mut self, is_disabled: bool) -> Self {
+        self.is_disabled = is_disabled;
+
+        if is_disabled {
+            self.state.unfocus();
+        }
+
+        self
+    }
+
+    /// Highlights a track, indexed like the pattern, e.g. the `focused_track`
+    /// kept up to date by `manage_state_update`.
+    pub fn focused_track(mut self, track: usize) -> Self {
+        let row = NUM_PERCS - 1 - track.min(NUM_PERCS - 1);
+
+        if self.state.focused_track != Some(row) {
+            // the track background is part of the grid
+            self.state.layers.clear_grid();
+            self.state.focused_track = Some(row);
+        }
+
+        self
+    }
+
+    /// The cached drawing is redrawn if the style differs from the one it was drawn with.
     pub fn style(mut self, style: impl Into<Renderer::Style>) -> Self {
-        self.state.event_cache.clear();
-        self.state.grid_cache.clear();
         self.style = style.into();
+        Renderer::restyle(&self.style, self.state.status.get(), &self.state.layers);
         self
     }
 
@@ -86,7 +197,7 @@ impl<'a, Message, Renderer: self::Renderer> Grid<'a, Message, Renderer> {
         match grid_messages {
             Some(messages) => {
                 // clear event cache to update events display
-                self.state.event_cache.clear();
+                self.state.layers.clear_events();
 
                 messages.into_iter().for_each(|message| {
                     messages_queue.publish((self.on_event)(message));
@@ -96,6 +207,37 @@ impl<'a, Message, Renderer: self::Renderer> Grid<'a, Message, Renderer> {
         }
     }
 
+    fn get_tooltip(&self, bounds: Rectangle, bpm: f32) -> Option<Tooltip> {
+        let position = self.state.cursor_position;
+        let viewport = self.state.context.viewport;
+
+        let (grid_id, event) = if self.state.context.velocity_readout.is_some() {
+            // the grabbed event keeps its step while its velocity is dragged
+            let grid_id = self.state.drag_anchor?;
+            (grid_id, *self.live_pattern.data.get(&grid_id)?)
+        } else if self.state.get_movement().is_some() {
+            // and stays under the cursor while it's moved
+            let (grid_id, event) = self.live_pattern.get_hovered(position, bounds, viewport)?;
+            (*grid_id, *event)
+        } else {
+            let (grid_id, deadline) = self.state.hovered?;
+
+            if Instant::now() < deadline {
+                return None;
+            }
+
+            (grid_id, *self.live_pattern.data.get(&grid_id)?)
+        };
+
+        Some(Tooltip {
+            position,
+            step: grid_id.0,
+            track: grid_id.1,
+            event,
+            bpm,
+        })
+    }
+
     fn handle_transition(&mut self, transition: Transition) {
         match transition {
             Transition::ChangeState(new_state) => {
@@ -108,13 +250,32 @@ impl<'a, Message, Renderer: self::Renderer> Grid<'a, Message, Renderer> {
             _ => {}
         }
     }
+
+    fn get_status(&self, is_hovered: bool) -> Status {
+        if self.is_disabled {
+            Status::Disabled
+        } else if self.state.get_movement().is_some() {
+            Status::Dragging
+        } else if self.state.is_focused {
+            Status::Focused
+        } else if is_hovered {
+            Status::Hovered
+        } else {
+            Status::Active
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct WidgetContext {
-    // base pattern we use as a base (sometimes modifications are not applied, ex: when you drag and press Escape)
     selection_rectangle: Option<Rectangle>,
+    is_deselecting: bool, // the selection rectangle removes events from the selection
     mouse_interaction: mouse::Interaction,
+    modifiers: keyboard::Modifiers,
+    viewport: Viewport,
+    snap: Snap,
+    velocity_readout: Option<f32>, // shown next to the cursor while dragging a velocity
+    event_inset: f32, // the event contour, velocity handles are only grabbed inside it
 }
 
 #[derive(Debug)]
@@ -123,13 +284,24 @@ pub struct State {
     context: WidgetContext,                     // context we'll mutate in our state machine
     base_pattern: GridPattern,
     temp_movement: Option<(f32, isize)>,
+    is_copying: bool,
     last_click: Option<mouse::Click>,
     highlight: [usize; NUM_PERCS],
+    playhead: Option<f32>,
     is_playing: bool,
-    grid_cache: canvas::Cache,
-    event_cache: canvas::Cache,
-    highlight_cache: canvas::Cache,
+    layers: Layers, // redrawn by the renderers caching them
     mutes: [bool; NUM_PERCS],
+    context_menu: Option<ContextMenu>,
+    is_focused: bool,
+    drag_anchor: Option<(usize, usize)>, // the event grabbed by the last click
+    is_drag_source: bool,                // the selection was dragged out of the grid
+    cursor_position: Point,
+    hovered: Option<((usize, usize), Instant)>, // shows the tooltip at this deadline
+    status: Cell<Status>, // the one last drawn
+    focused_track: Option<usize>, // grid track
+    reached: [Option<usize>; NUM_PERCS], // the step each grid track last reached while playing
+    hits: [Option<(usize, Instant)>; NUM_PERCS], // the last event each grid track reached, and when
+    now: Option<Instant>,               // the host's clock, for the flashes
 }
 
 impl State {
@@ -138,75 +310,200 @@ impl State {
             current_state: Box::new(Idle::default()),
             context: WidgetContext {
                 selection_rectangle: None,
+                is_deselecting: false,
                 mouse_interaction: mouse::Interaction::default(),
+                modifiers: keyboard::Modifiers::default(),
+                viewport: Viewport::default(),
+                snap: Snap::default(),
+                velocity_readout: None,
+                event_inset: 0.,
             },
             base_pattern: grid,
             temp_movement: None,
+            is_copying: false,
             last_click: None,
             highlight: [0; NUM_PERCS],
+            playhead: None,
             is_playing: false,
-            grid_cache: Default::default(),
-            event_cache: Default::default(),
-            highlight_cache: Default::default(),
+            layers: Layers::new(),
             mutes: [false; NUM_PERCS],
+            context_menu: None,
+            is_focused: false,
+            drag_anchor: None,
+            is_drag_source: false,
+            cursor_position: Point::ORIGIN,
+            hovered: None,
+            status: Cell::new(Status::Active),
+            focused_track: None,
+            reached: [None; NUM_PERCS],
+            hits: [None; NUM_PERCS],
+            now: None,
         }
     }
 
+    /// Redraws the whole grid on the next frame, e.g. when its style changed.
+    pub fn clear_caches(&self) {
+        self.layers.clear();
+    }
+
     pub fn set_pattern(&mut self, grid: GridPattern) {
-        self.event_cache.clear();
+        self.layers.clear_events();
         self.temp_movement = None;
+        self.is_copying = false;
         self.base_pattern = grid;
     }
 
-    pub fn set_movement(&mut self, movement: (f32, isize), relative: bool) {
-        match self.temp_movement {
-            Some(current_movement) => {
-                if relative {
-                    self.temp_movement = Some((
-                        (current_movement.0 + movement.0) % NUM_STEPS as f32,
-                        current_movement.1 + movement.1
-                    ));
-                } else {
-                    self.temp_movement = Some(movement);
-                }
-            },
-            None => {
-                self.temp_movement = Some(movement);
-            },
-        }
+    pub fn start_movement(&mut self, copy: bool) {
+        self.temp_movement = None;
+        self.is_copying = copy;
+    }
+
+    pub fn set_movement(&mut self, movement: (f32, isize)) {
+        self.temp_movement = Some(movement);
+    }
+
+    pub fn discard_movement(&mut self) {
+        self.layers.clear_events();
+        self.temp_movement = None;
+        self.is_copying = false;
     }
 
     pub fn get_movement(&self) -> Option<(f32, isize)> {
         return self.temp_movement;
     }
 
+    pub fn is_copying(&self) -> bool {
+        self.is_copying
+    }
+
     pub fn clone_base_pattern(&self) -> GridPattern {
         self.base_pattern.clone()
     }
 
+    /// Gives the keyboard focus to the grid, it gains it on click otherwise.
+    pub fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.is_focused = false;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    /// When the tooltip of the hovered event is due, if it isn't shown yet.
+    ///
+    /// Nothing is redrawn while the cursor rests on an event, so the host
+    /// should request a redraw at this instant, e.g. with a subscription.
+    pub fn tooltip_deadline(&self) -> Option<Instant> {
+        self.hovered
+            .map(|(_, deadline)| deadline)
+            .filter(|deadline| *deadline > Instant::now())
+    }
+
+    pub fn get_viewport(&self) -> Viewport {
+        self.context.viewport
+    }
+
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.context.viewport = viewport;
+    }
+
     pub fn is_playing(&mut self, is_playing: bool) {
+        // the played events move between the event and highlight layers
+        // the steps are reached again from the start, the first one flashing too
+        if self.is_playing != is_playing {
+            self.layers.clear_events();
+            self.reached = [None; NUM_PERCS];
+            self.hits = [None; NUM_PERCS];
+        }
+
         self.is_playing = is_playing;
     }
 
+    // the static events stay cached, only the steps left and reached are redrawn
     pub fn transport(&mut self, highlight: [Option<usize>; NUM_PERCS]) {
-        self.event_cache.clear();
+        let previous_highlight = self.highlight;
+
         for (pidx, option_step) in highlight.iter().enumerate() {
             match option_step {
                 Some(step) => self.highlight[NUM_PERCS - pidx - 1] = *step,
                 None => {}
             }
         }
+
+        if self.highlight != previous_highlight {
+            for (step, previous_step) in self.highlight.iter().zip(previous_highlight.iter()) {
+                if step != previous_step {
+                    self.layers.clear_step(*step);
+                    self.layers.clear_step(*previous_step);
+                }
+            }
+
+            self.layers.clear_highlight();
+        }
+    }
+
+    /// Like [`State::transport`], the tracks reaching another step at `now`
+    /// flash if they have an event there, the first step played included.
+    ///
+    /// A flash is kept until the next event of its track is reached, the
+    /// renderer stops drawing it once faded out.
+    pub fn transport_at(&mut self, highlight: [Option<usize>; NUM_PERCS], now: Instant) {
+        self.transport(highlight);
+
+        for (track, step) in self.highlight.iter().enumerate() {
+            if self.reached[track] == Some(*step) {
+                continue;
+            }
+
+            self.reached[track] = Some(*step);
+
+            if self.base_pattern.data.contains_key(&(*step, track)) {
+                self.hits[track] = Some((*step, now));
+            }
+        }
+
+        self.animate(now);
+    }
+
+    /// Advances the flashes to `now`, e.g. on each frame of the host while
+    /// playing. They're drawn apart from the caches, which stay untouched.
+    pub fn animate(&mut self, now: Instant) {
+        self.now = Some(now);
+    }
+
+    // grid track => (hit step, time since it was hit)
+    fn get_flashes(&self) -> [Option<(usize, Duration)>; NUM_PERCS] {
+        let mut flashes = [None; NUM_PERCS];
+
+        if let (true, Some(now)) = (self.is_playing, self.now) {
+            for (track, hit) in self.hits.iter().enumerate() {
+                flashes[track] = hit.map(|(step, hit)| (step, now.saturating_duration_since(hit)));
+            }
+        }
+
+        flashes
+    }
+
+    /// Sets the playhead position in steps, fractions included, e.g. 4.5 is
+    /// halfway through the fifth step. It's drawn as a line while playing.
+    pub fn set_playhead(&mut self, position: Option<f32>) {
+        self.playhead = position.map(|position| position.max(0.).min(NUM_STEPS as f32));
     }
 
     pub fn set_mute(&mut self, pidx: usize, mute: bool) {
-        self.event_cache.clear();
+        self.layers.clear_events();
         self.mutes[NUM_PERCS - pidx - 1] = mute;
     }
 }
 
 impl<'a, Message, Renderer> Widget<Message, Renderer> for Grid<'a, Message, Renderer>
 where
-    Renderer: self::Renderer,
+    Renderer: 'a + self::Renderer,
+    Message: 'a,
 {
     fn width(&self) -> Length {
         self.width
@@ -238,12 +535,18 @@ where
         event: Event,
         layout: Layout<'_>,
         cursor_position: Point,
-        _renderer: &Renderer,
+        renderer: &Renderer,
         _clipboard: &mut dyn Clipboard,
         messages: &mut Shell<'_, Message>,
     ) -> event::Status {
         let bounds = layout.children().next().unwrap().bounds();
 
+        if self.is_disabled {
+            return event::Status::Ignored;
+        }
+
+        self.state.context.event_inset = renderer.event_inset(&self.style);
+
         // dispatch events to our state machine whose states (modes) and substates are defined
         // in ./modes
 
@@ -268,12 +571,65 @@ where
                         messages,
                     );
 
+                    self.state.cursor_position = cursor_position;
+
+                    let hovered = self
+                        .live_pattern
+                        .get_hovered(cursor_position, bounds, self.state.context.viewport)
+                        .map(|(grid_id, _)| *grid_id);
+
+                    // the tooltip delay restarts from each newly hovered event
+                    if hovered != self.state.hovered.map(|(grid_id, _)| grid_id) {
+                        let deadline = Instant::now() + self.tooltip_delay;
+                        self.state.hovered = hovered
+                            .filter(|_| self.tooltip_bpm.is_some())
+                            .map(|grid_id| (grid_id, deadline));
+                    }
+
+                    // a moved selection leaving the grid becomes a drag between widgets
+                    if let (Some(on_drag), Some(anchor)) = (&self.on_drag, self.state.drag_anchor) {
+                        if !self.state.is_drag_source
+                            && self.state.get_movement().is_some()
+                            && !bounds.contains(cursor_position)
+                        {
+                            self.state.is_drag_source = true;
+
+                            let payload = self.state.base_pattern.get_drag_payload(anchor, true);
+                            messages.publish(on_drag(DragMessage::Picked(payload)));
+                        }
+                    }
+
                     return event::Status::Captured;
                 }
                 mouse::Event::ButtonPressed(mouse::Button::Left) => {
                     if bounds.contains(cursor_position) {
                         let click = mouse::Click::new(cursor_position, self.state.last_click);
 
+                        // modifiers held while unfocused pick the mode the click starts in
+                        if !self.state.is_focused {
+                            self.state.focus();
+
+                            let modifiers = self.state.context.modifiers;
+                            self.handle_event(
+                                |widget_state, context, _| {
+                                    widget_state.on_modifier_change(modifiers, context)
+                                },
+                                messages,
+                            );
+                        }
+
+                        let viewport = self.state.context.viewport;
+                        let base_pattern = &self.state.base_pattern;
+                        self.state.drag_anchor = base_pattern
+                            .get_hovered_velocity_handle(
+                                cursor_position,
+                                bounds,
+                                viewport,
+                                self.state.context.event_inset,
+                            )
+                            .or_else(|| base_pattern.get_hovered(cursor_position, bounds, viewport))
+                            .map(|(grid_id, _)| *grid_id);
+
                         match click.kind() {
                             mouse::click::Kind::Single => {
                                 self.handle_event(
@@ -307,7 +663,8 @@ where
                         self.state.last_click = Some(click);
 
                         return event::Status::Captured;
-                    } else {
+                    } else if self.state.is_focused {
+                        self.state.unfocus();
                         self.handle_event(
                             |widget_state, _context, _base_pattern| {
                                 widget_state.on_blur()
@@ -316,49 +673,154 @@ where
                         );
                     }
                 }
-                mouse::Event::ButtonReleased(mouse::Button::Left) => {
-                    self.handle_event(
-                        |widget_state, context, base_pattern| {
-                            widget_state.on_button_release(
-                                bounds,
+                mouse::Event::ButtonPressed(mouse::Button::Right) => {
+                    if bounds.contains(cursor_position) {
+                        self.state.focus();
+
+                        let track = get_hovered_track(cursor_position, bounds);
+                        let mut grid_messages = vec![GridMessage::TrackSelected(track)];
+
+                        // the menu acts on the selection, so a right click on an
+                        // unselected event selects it first
+                        if let Some(((step, track), grid_event)) =
+                            self.state.base_pattern.get_hovered(
                                 cursor_position,
-                                base_pattern,
-                                context,
+                                bounds,
+                                self.state.context.viewport,
                             )
-                        },
-                        messages,
-                    );
+                        {
+                            if !grid_event.selected {
+                                grid_messages.push(GridMessage::SelectOne((*step, *track)));
+                            }
+                        }
+
+                        self.state.context_menu = Some(ContextMenu {
+                            position: cursor_position,
+                            track,
+                        });
+                        self.state.layers.clear_events();
+
+                        grid_messages.into_iter().for_each(|message| {
+                            messages.publish((self.on_event)(message));
+                        });
+
+                        return event::Status::Captured;
+                    }
+                }
+                mouse::Event::WheelScrolled { delta } => {
+                    if bounds.contains(cursor_position) {
+                        let viewport = &mut self.state.context.viewport;
+                        let (x, y) = match delta {
+                            mouse::ScrollDelta::Lines { x, y } => (x, y),
+                            mouse::ScrollDelta::Pixels { x, y } => {
+                                let step_width =
+                                    get_step_width(viewport.get_content_size(bounds.size()));
+
+                                (x / step_width, y / step_width)
+                            }
+                        };
+
+                        // Ctrl+wheel zooms around the cursor, the horizontal wheel scrolls,
+                        // the vertical one is left to the parent widgets
+                        if self.state.context.modifiers.control() && y != 0. {
+                            viewport.zoom(y, (cursor_position.x - bounds.x) / bounds.width);
+                        } else if x != 0. {
+                            viewport.scroll(-x);
+                        } else {
+                            return event::Status::Ignored;
+                        }
+
+                        return event::Status::Captured;
+                    }
+                }
+                mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                    let is_inside = bounds.contains(cursor_position);
+
+                    if let Some(on_drag) = &self.on_drag {
+                        let message = match &self.drag_payload {
+                            // the grid under the cursor takes the drop
+                            Some(payload) if is_inside && !self.state.is_drag_source => {
+                                let viewport = self.state.context.viewport;
+                                let (step, track, _) =
+                                    get_hovered_step(cursor_position, bounds, viewport, true);
+
+                                Some(DragMessage::Dropped(payload.clone(), (step, track)))
+                            }
+                            // and the one it was dragged out of ends the drag
+                            _ if self.state.is_drag_source => Some(DragMessage::Canceled()),
+                            _ => None,
+                        };
+
+                        if let Some(message) = message {
+                            messages.publish(on_drag(message));
+                        }
+                    }
+
+                    let is_dropped_outside = self.state.is_drag_source && !is_inside;
+
+                    self.state.drag_anchor = None;
+                    self.state.is_drag_source = false;
+
+                    match is_dropped_outside {
+                        true => self.handle_event(
+                            |widget_state, context, _| widget_state.on_dropped_outside(context),
+                            messages,
+                        ),
+                        false => self.handle_event(
+                            |widget_state, context, base_pattern| {
+                                widget_state.on_button_release(
+                                    bounds,
+                                    cursor_position,
+                                    base_pattern,
+                                    context,
+                                )
+                            },
+                            messages,
+                        ),
+                    }
 
                     return event::Status::Captured;
                 }
                 _ => {}
             },
+            // keys typed in other widgets are none of our business
             Event::Keyboard(keyboard_event) => match keyboard_event {
-                keyboard::Event::KeyPressed { key_code, .. } => {
-                    self.handle_event(
-                        |widget_state, context, _| widget_state.on_key_pressed(key_code, context),
-                        messages,
-                    );
+                keyboard::Event::KeyPressed { key_code, modifiers } if self.state.is_focused => {
+                    if let Some(action) = self.key_bindings.get(key_code, modifiers) {
+                        self.handle_event(
+                            |widget_state, context, _| widget_state.on_key_pressed(action, context),
+                            messages,
+                        );
 
-                    return event::Status::Captured;
+                        return event::Status::Captured;
+                    }
                 }
-                keyboard::Event::KeyReleased { key_code, .. } => {
+                keyboard::Event::KeyReleased { key_code, modifiers } if self.state.is_focused => {
                     self.handle_event(
                         |widget_state, context, _| widget_state.on_key_released(key_code, context),
                         messages,
                     );
 
-                    return event::Status::Captured;
+                    // the unbound keys are left to the host shortcuts
+                    if self.key_bindings.get(key_code, modifiers).is_some() {
+                        return event::Status::Captured;
+                    }
                 }
                 keyboard::Event::ModifiersChanged(modifiers) => {
-                    self.handle_event(
-                        |widget_state, context, _| {
-                            widget_state.on_modifier_change(modifiers, context)
-                        },
-                        messages,
-                    );
+                    // modifiers are tracked even without focus, a click may depend on them
+                    self.state.context.modifiers = modifiers;
 
-                    return event::Status::Captured;
+                    // but only the focused grid changes mode
+                    if self.state.is_focused {
+                        self.handle_event(
+                            |widget_state, context, _| {
+                                widget_state.on_modifier_change(modifiers, context)
+                            },
+                            messages,
+                        );
+
+                        return event::Status::Captured;
+                    }
                 }
                 _ => {}
             },
@@ -376,22 +838,66 @@ where
         cursor_position: Point,
         _viewport: &Rectangle,
     ) {
+        let drawable_area = layout.children().next().unwrap().bounds();
+        let status = self.get_status(drawable_area.contains(cursor_position));
+
+        // each status has its own style, the caches are only redrawn if it differs
+        self.state.status.set(status);
+        Renderer::restyle(&self.style, status, &self.state.layers);
+
         renderer.draw(
             layout.bounds(),
-            layout.children().next().unwrap().bounds(),
+            drawable_area,
             cursor_position,
             &self.live_pattern,
+            // the original positions of a moved selection
+            match (self.state.get_movement(), self.state.is_copying()) {
+                (Some(_), false) => Some(&self.state.base_pattern),
+                _ => None,
+            },
+            self.state.context.viewport,
+            self.state.context.snap,
+            status,
+            self.state.focused_track,
             self.state.context.selection_rectangle,
+            self.state.context.is_deselecting,
+            self.state.context.velocity_readout,
             self.state.context.mouse_interaction,
             self.state.is_playing,
             self.state.highlight,
+            self.state.playhead,
+            self.state.get_flashes(),
             self.state.mutes,
+            self.state.is_focused,
             &self.style,
-            &self.state.grid_cache,
-            &self.state.event_cache,
-            &self.state.highlight_cache,
+            &self.state.layers,
         )
     }
+
+    fn overlay(
+        &mut self,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+    ) -> Option<overlay::Element<'_, Message, Renderer>> {
+        if let Some(menu) = self.state.context_menu {
+            return Some(overlay::Element::new(
+                menu.position,
+                Box::new(ContextMenuOverlay::new(
+                    &mut self.state.context_menu,
+                    self.on_event.as_ref(),
+                    &self.style,
+                )),
+            ));
+        }
+
+        let bounds = layout.children().next().unwrap().bounds();
+        let tooltip = self.get_tooltip(bounds, self.tooltip_bpm?)?;
+
+        Some(overlay::Element::new(
+            tooltip.position,
+            Box::new(TooltipOverlay::new(tooltip, &self.style)),
+        ))
+    }
 }
 pub trait Renderer: iced_native::Renderer {
     /// The style supported by this renderer.
@@ -403,16 +909,44 @@ pub trait Renderer: iced_native::Renderer {
         drawable_area: Rectangle,
         cursor_position: Point,
         grid_pattern: &GridPattern,
+        ghost_pattern: Option<&GridPattern>,
+        viewport: Viewport,
+        snap: Snap,
+        status: Status,
+        focused_track: Option<usize>,
         selection: Option<Rectangle>,
+        is_deselecting: bool,
+        velocity_readout: Option<f32>,
         mouse_interaction: mouse::Interaction,
         is_playing: bool,
         highlight: [usize; NUM_PERCS],
+        playhead: Option<f32>,
+        flashes: [Option<(usize, Duration)>; NUM_PERCS], // grid track => (step, elapsed)
         mutes: [bool; NUM_PERCS],
+        is_focused: bool,
+        style: &Self::Style,
+        layers: &Layers,
+    );
+
+    fn draw_context_menu(
+        &mut self,
+        bounds: Rectangle,
+        actions: &[MenuAction],
+        hovered: Option<usize>,
         style: &Self::Style,
-        grid_cache: &canvas::Cache,
-        event_cache: &canvas::Cache,
-        highlight_cache: &canvas::Cache,
     );
+
+    fn draw_tooltip(&mut self, bounds: Rectangle, lines: &[String], style: &Self::Style);
+
+    /// Called before drawing with `status`, so renderers caching the grid can
+    /// redraw its [`Layers`] when the resolved style changed, see [`Layers::restyle`].
+    fn restyle(_style: &Self::Style, _status: Status, _layers: &Layers) {}
+
+    /// The width of the contour drawn inside events, which isn't part of
+    /// their velocity bar.
+    fn event_inset(&self, _style: &Self::Style) -> f32 {
+        0.
+    }
 }
 
 impl<'a, Message, Renderer> From<Grid<'a, Message, Renderer>> for Element<'a, Message, Renderer>
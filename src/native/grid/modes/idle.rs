@@ -1,8 +1,8 @@
 use super::LogoCtrl;
 use super::Shift;
-use super::{Transition, WidgetContext, WidgetState};
+use super::{restore_selection, GridAction, Transition, WidgetContext, WidgetState};
 use crate::core::grid::{
-    get_hovered_step, get_hovered_track, get_step_width,
+    get_hovered_step, get_hovered_track, get_step_dimensions, get_step_width,
     GridEvent, GridMessage, GridPattern
 };
 use iced_native::{keyboard, mouse, Point, Rectangle};
@@ -91,27 +91,40 @@ impl WidgetState for Idle {
         modifiers: keyboard::Modifiers,
         context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
-        let transition: Transition = self.nested.on_modifier_change(modifiers, context).0;
+        let (transition, messages) = self.nested.on_modifier_change(modifiers, context);
 
         match transition {
             Transition::ChangeState(new_state) => {
                 self.next(new_state);
-                return (Transition::DoNothing, None);
+                return (Transition::DoNothing, messages);
             }
             Transition::ChangeParentState(new_state) => {
-                return (Transition::ChangeState(new_state), None)
+                return (Transition::ChangeState(new_state), messages)
             }
-            Transition::DoNothing => return (Transition::DoNothing, None),
+            Transition::DoNothing => return (Transition::DoNothing, messages),
         }
     }
 
     fn on_key_pressed(
         &mut self,
-        key_code: keyboard::KeyCode,
+        action: GridAction,
         context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
         let (next_transition, messages) =
-            self.nested.on_key_pressed(key_code, context);
+            self.nested.on_key_pressed(action, context);
+
+        if let Transition::ChangeState(new_state) = next_transition {
+            self.next(new_state);
+        }
+
+        (Transition::DoNothing, messages)
+    }
+
+    fn on_dropped_outside(
+        &mut self,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        let (next_transition, messages) = self.nested.on_dropped_outside(context);
 
         if let Transition::ChangeState(new_state) = next_transition {
             self.next(new_state);
@@ -134,9 +147,10 @@ impl Default for Idle {
 }
 
 impl Idle {
-    pub fn selecting(point: Point) -> Idle {
+    // an area selection carried over from another mode
+    pub fn selecting(point: Point, origin_selection: Vec<(usize, usize)>) -> Idle {
         Idle {
-            nested: Box::new(Selecting::from_args(point)),
+            nested: Box::new(Selecting::from_args(point, false, origin_selection)),
         }
     }
 }
@@ -153,7 +167,7 @@ impl WidgetState for Waiting {
         bounds: Rectangle,
         cursor: Point,
         base_pattern: GridPattern,
-        _context: &mut WidgetContext,
+        context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
         let mut grid_messages = vec![
             GridMessage::TrackSelected(get_hovered_track(cursor, bounds)),
@@ -161,23 +175,24 @@ impl WidgetState for Waiting {
         ];
 
         // check if we hover an event on the grid
-        match base_pattern.get_hovered(cursor, bounds) {
+        match base_pattern.get_hovered(cursor, bounds, context.viewport) {
             // if yes remove event
             Some((grid_id, _grid_event)) => {
                 grid_messages.push(GridMessage::Delete(*grid_id));
             },
             // otherwise add event
             None => {
-                let step_width = get_step_width(bounds.size());
+                let content_bounds = context.viewport.get_content_bounds(bounds);
+                let step_width = get_step_width(content_bounds.size());
                 let interactive_area = Rectangle {
-                    x: bounds.x + step_width,
-                    y: bounds.y,
-                    width: bounds.width - 2. * step_width,
-                    height: bounds.height,
+                    x: content_bounds.x + step_width,
+                    y: content_bounds.y,
+                    width: content_bounds.width - 2. * step_width,
+                    height: content_bounds.height,
                 };
 
                 if interactive_area.contains(cursor) {
-                    let (step, track, _) = get_hovered_step(cursor, bounds, true);
+                    let (step, track, _) = get_hovered_step(cursor, bounds, context.viewport, true);
                     grid_messages.push(GridMessage::Add((step, track, 0.)));
                 }
             }
@@ -197,15 +212,43 @@ impl WidgetState for Waiting {
             GridMessage::TrackSelected(get_hovered_track(cursor, bounds))
         ];
 
+        // check if we grab the top of a velocity bar
+        if let Some(((step, track), grid_event)) =
+            base_pattern.get_hovered_velocity_handle(
+                cursor,
+                bounds,
+                context.viewport,
+                context.event_inset,
+            )
+        {
+            if !grid_event.selected {
+                grid_messages.push(GridMessage::EmptySelection());
+                grid_messages.push(GridMessage::SelectOne((*step, *track)));
+            }
+
+            context.mouse_interaction = mouse::Interaction::ResizingVertically;
+            context.velocity_readout = Some(grid_event.velocity);
+
+            return (
+                Transition::ChangeState(Box::new(DraggingVelocity::from_args(
+                    cursor,
+                    grid_event.velocity,
+                ))),
+                Some(grid_messages),
+            );
+        }
+
         // check if we hover an event on the grid
-        match base_pattern.get_hovered(cursor, bounds) {
+        match base_pattern.get_hovered(cursor, bounds, context.viewport) {
             // if yes select only this event and start grabbing
+            // holding Alt grabs a copy of the selection instead
             Some(((step, track), grid_event)) => {
                 if !grid_event.selected {
                     grid_messages.push(GridMessage::EmptySelection());
                     grid_messages.push(GridMessage::SelectOne((*step, *track)));
                 }
 
+                grid_messages.push(GridMessage::MoveStarted(context.modifiers.alt()));
                 context.mouse_interaction = mouse::Interaction::Grab;
 
                 (
@@ -217,17 +260,62 @@ impl WidgetState for Waiting {
                 )
             }
             // otherwise change to area selection mode
+            // holding Alt removes the area from the selection instead
             None => {
-                grid_messages.push(GridMessage::EmptySelection());
+                let content_bounds = context.viewport.get_content_bounds(bounds);
+                let step_width = get_step_width(content_bounds.size());
+
+                // unless the left edge step is clicked, which selects the whole track
+                if cursor.x < content_bounds.x + step_width {
+                    grid_messages.push(GridMessage::SelectTrack(get_hovered_track(cursor, bounds)));
+
+                    return (Transition::DoNothing, Some(grid_messages));
+                }
+
+                let is_deselecting = context.modifiers.alt();
+                // saved before it's emptied, in case the user cancels
+                let origin_selection = base_pattern.get_selection();
+
+                if !is_deselecting {
+                    grid_messages.push(GridMessage::EmptySelection());
+                }
 
                 (
-                    Transition::ChangeState(Box::new(Selecting::from_args(cursor))),
+                    Transition::ChangeState(Box::new(Selecting::from_args(
+                        cursor,
+                        is_deselecting,
+                        origin_selection,
+                    ))),
                     Some(grid_messages),
                 )
             }
         }
     }
 
+    fn on_cursor_moved(
+        &mut self,
+        bounds: Rectangle,
+        cursor: Point,
+        base_pattern: GridPattern,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        match base_pattern.get_hovered_velocity_handle(
+                cursor,
+                bounds,
+                context.viewport,
+                context.event_inset,
+            ) {
+            Some(_) => {
+                context.mouse_interaction = mouse::Interaction::ResizingVertically;
+            }
+            None => {
+                context.mouse_interaction = mouse::Interaction::default();
+            }
+        }
+
+        (Transition::DoNothing, None)
+    }
+
     fn on_modifier_change(
         &mut self,
         modifiers: keyboard::Modifiers,
@@ -250,30 +338,28 @@ impl WidgetState for Waiting {
 
     fn on_key_pressed(
         &mut self,
-        key_code: keyboard::KeyCode,
-        _context: &mut WidgetContext,
+        action: GridAction,
+        context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
-        let grid_message = match key_code {
-            keyboard::KeyCode::A => Some(vec![GridMessage::EmptySelection(), GridMessage::SelectAll()]),
-            keyboard::KeyCode::Backspace => Some(vec![GridMessage::DeleteSelection()]),
-            keyboard::KeyCode::Left => Some(vec![GridMessage::MoveSelection((-1., 0), true)]),
-            keyboard::KeyCode::Up => Some(vec![GridMessage::MoveSelection((0., -1), true)]),
-            keyboard::KeyCode::Right => Some(vec![GridMessage::MoveSelection((1., 0), true)]),
-            keyboard::KeyCode::Down => Some(vec![GridMessage::MoveSelection((0., 1), true)]),
-            _ => None,
-        };
-
-        (Transition::DoNothing, grid_message)
+        (Transition::DoNothing, Some(action.messages(context.snap)))
     }
 }
 
 #[derive(Debug, Default)]
 struct Selecting {
     origin: Point,
+    origin_selection: Vec<(usize, usize)>, // the selection before the click, restored on cancel
+    is_deselecting: bool,
 }
 
 impl Selecting {
-    fn from_args(point: Point) -> Self { Selecting { origin: point } }
+    fn from_args(point: Point, is_deselecting: bool, origin_selection: Vec<(usize, usize)>) -> Self {
+        Selecting {
+            origin: point,
+            origin_selection,
+            is_deselecting,
+        }
+    }
 }
 
 impl WidgetState for Selecting {
@@ -309,11 +395,25 @@ impl WidgetState for Selecting {
 
         // display selection Rectangle
         context.selection_rectangle = Some(selection);
+        context.is_deselecting = self.is_deselecting;
+
+        // the pattern is hit-tested in content coordinates
+        let viewport = context.viewport;
+        let content_selection =
+            viewport.convert_rectangle_to_content_coordinates(selection, bounds.size());
+        let content_size = viewport.get_content_size(bounds.size());
+
+        let grid_messages = match self.is_deselecting {
+            // from the selection the drag started with, so shrinking the area reselects events
+            true => {
+                let mut grid_messages = restore_selection(&self.origin_selection);
+                grid_messages.push(GridMessage::RemoveSelectedArea(content_selection, content_size));
+                grid_messages
+            }
+            false => vec![GridMessage::SelectArea(content_selection, content_size)],
+        };
 
-        (
-            Transition::DoNothing,
-            Some(vec![GridMessage::SelectArea(selection, bounds.size())]),
-        )
+        (Transition::DoNothing, Some(grid_messages))
     }
 
     fn on_button_release(
@@ -325,9 +425,30 @@ impl WidgetState for Selecting {
     ) -> (Transition, Option<Vec<GridMessage>>) {
         // erase selection Rectangle
         context.selection_rectangle = None;
+        context.is_deselecting = false;
 
         (Transition::ChangeState(Box::new(Waiting::default())), None)
     }
+
+    fn on_key_pressed(
+        &mut self,
+        action: GridAction,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        match action {
+            GridAction::Cancel => {
+                // erase selection Rectangle
+                context.selection_rectangle = None;
+                context.is_deselecting = false;
+
+                (
+                    Transition::ChangeState(Box::new(Waiting::default())),
+                    Some(restore_selection(&self.origin_selection)),
+                )
+            }
+            _ => (Transition::DoNothing, None),
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -351,7 +472,7 @@ impl WidgetState for MovingSelectionQuantized {
         bounds: Rectangle,
         cursor: Point,
         base_pattern: GridPattern,
-        _context: &mut WidgetContext,
+        context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
         let drag_bounds = Rectangle {
             x: self.origin.x,
@@ -361,19 +482,24 @@ impl WidgetState for MovingSelectionQuantized {
         };
 
         // debounce a bit
-        let movement =
-            base_pattern.move_selection_quantized(bounds, drag_bounds, cursor, self.origin_event);
+        let movement = base_pattern.move_selection_quantized(
+            context.viewport.get_content_bounds(bounds),
+            drag_bounds,
+            cursor,
+            self.origin_event,
+            context.snap,
+        );
 
         // if movement.0 != 0. || movement.1 != 0 {
         //     return (
         //         Transition::DoNothing,
-        //         Some(vec![GridMessage::MoveSelection(movement, false)]),
+        //         Some(vec![GridMessage::MoveUpdated(movement)]),
         //     );
         // }
 
         (
             Transition::DoNothing,
-            Some(vec![GridMessage::MoveSelection(movement, false)]),
+            Some(vec![GridMessage::MoveUpdated(movement)]),
         )
     }
 
@@ -405,7 +531,41 @@ impl WidgetState for MovingSelectionQuantized {
         // erase selection Rectangle
         context.selection_rectangle = None;
 
-        (Transition::ChangeState(Box::new(Waiting::default())), None)
+        (
+            Transition::ChangeState(Box::new(Waiting::default())),
+            Some(vec![GridMessage::MoveCommitted()]),
+        )
+    }
+
+    fn on_key_pressed(
+        &mut self,
+        action: GridAction,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        match action {
+            GridAction::Cancel => {
+                context.mouse_interaction = mouse::Interaction::default();
+
+                (
+                    Transition::ChangeState(Box::new(Waiting::default())),
+                    Some(vec![GridMessage::DiscardState()]),
+                )
+            }
+            _ => (Transition::DoNothing, None),
+        }
+    }
+
+    // the events were dropped on another widget, the selection stays where it was
+    fn on_dropped_outside(
+        &mut self,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        context.mouse_interaction = mouse::Interaction::default();
+
+        (
+            Transition::ChangeState(Box::new(Waiting::default())),
+            Some(vec![GridMessage::DiscardState()]),
+        )
     }
 }
 
@@ -430,7 +590,7 @@ impl WidgetState for MovingSelectionUnquantized {
         bounds: Rectangle,
         cursor: Point,
         base_pattern: GridPattern,
-        _context: &mut WidgetContext,
+        context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
         let drag_bounds = Rectangle {
             x: self.origin.x,
@@ -441,7 +601,7 @@ impl WidgetState for MovingSelectionUnquantized {
 
         if drag_bounds.width.abs() >= 1. || drag_bounds.height.abs() >= 1. {
             let movement = base_pattern.move_selection_unquantized(
-                bounds,
+                context.viewport.get_content_bounds(bounds),
                 drag_bounds,
                 cursor,
                 self.origin_event,
@@ -449,7 +609,7 @@ impl WidgetState for MovingSelectionUnquantized {
 
             return (
                 Transition::DoNothing,
-                Some(vec![GridMessage::MoveSelection(movement, false)]),
+                Some(vec![GridMessage::MoveUpdated(movement)]),
             );
         }
 
@@ -484,6 +644,114 @@ impl WidgetState for MovingSelectionUnquantized {
         // erase selection Rectangle
         context.selection_rectangle = None;
 
-        (Transition::ChangeState(Box::new(Waiting::default())), None)
+        (
+            Transition::ChangeState(Box::new(Waiting::default())),
+            Some(vec![GridMessage::MoveCommitted()]),
+        )
+    }
+
+    fn on_key_pressed(
+        &mut self,
+        action: GridAction,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        match action {
+            GridAction::Cancel => {
+                context.mouse_interaction = mouse::Interaction::default();
+
+                (
+                    Transition::ChangeState(Box::new(Waiting::default())),
+                    Some(vec![GridMessage::DiscardState()]),
+                )
+            }
+            _ => (Transition::DoNothing, None),
+        }
+    }
+
+    // the events were dropped on another widget, the selection stays where it was
+    fn on_dropped_outside(
+        &mut self,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        context.mouse_interaction = mouse::Interaction::default();
+
+        (
+            Transition::ChangeState(Box::new(Waiting::default())),
+            Some(vec![GridMessage::DiscardState()]),
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct DraggingVelocity {
+    origin: Point,
+    origin_velocity: f32,
+}
+
+impl DraggingVelocity {
+    fn from_args(point: Point, velocity: f32) -> Self {
+        DraggingVelocity {
+            origin: point,
+            origin_velocity: velocity,
+        }
+    }
+}
+
+impl WidgetState for DraggingVelocity {
+    fn on_cursor_moved(
+        &mut self,
+        bounds: Rectangle,
+        cursor: Point,
+        _base_pattern: GridPattern,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        // the grabbed event follows the cursor, the rest of the selection
+        // is shifted by the same amount
+        let step_height = get_step_dimensions(bounds.size()).height;
+        let velocity = (self.origin_velocity + (self.origin.y - cursor.y) / step_height)
+            .min(1.)
+            .max(0.);
+
+        context.velocity_readout = Some(velocity);
+
+        (
+            Transition::DoNothing,
+            Some(vec![GridMessage::ShiftVelocity(velocity - self.origin_velocity)]),
+        )
+    }
+
+    fn on_button_release(
+        &mut self,
+        _bounds: Rectangle,
+        _cursor: Point,
+        _base_pattern: GridPattern,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        context.velocity_readout = None;
+        context.mouse_interaction = mouse::Interaction::default();
+
+        (
+            Transition::ChangeState(Box::new(Waiting::default())),
+            Some(vec![GridMessage::CommitState()]),
+        )
+    }
+
+    fn on_key_pressed(
+        &mut self,
+        action: GridAction,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        match action {
+            GridAction::Cancel => {
+                context.velocity_readout = None;
+                context.mouse_interaction = mouse::Interaction::default();
+
+                (
+                    Transition::ChangeState(Box::new(Waiting::default())),
+                    Some(vec![GridMessage::DiscardState()]),
+                )
+            }
+            _ => (Transition::DoNothing, None),
+        }
     }
 }
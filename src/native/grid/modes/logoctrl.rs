@@ -5,7 +5,7 @@ use crate::core::grid::{
     GridMessage, GridPattern
 };
 
-use super::{Idle, Transition, WidgetContext, WidgetState};
+use super::{restore_selection, GridAction, Idle, Transition, WidgetContext, WidgetState};
 
 #[derive(Debug)]
 pub struct LogoCtrl {
@@ -91,26 +91,26 @@ impl WidgetState for LogoCtrl {
         modifiers: keyboard::Modifiers,
         context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
-        let transition: Transition = self.nested.on_modifier_change(modifiers, context).0;
+        let (transition, messages) = self.nested.on_modifier_change(modifiers, context);
 
         match transition {
             Transition::ChangeState(new_state) => {
                 self.next(new_state);
-                return (Transition::DoNothing, None);
+                return (Transition::DoNothing, messages);
             }
             Transition::ChangeParentState(new_state) => {
-                return (Transition::ChangeState(new_state), None)
+                return (Transition::ChangeState(new_state), messages)
             }
-            Transition::DoNothing => return (Transition::DoNothing, None),
+            Transition::DoNothing => return (Transition::DoNothing, messages),
         }
     }
 
     fn on_key_pressed(
         &mut self,
-        key_code: keyboard::KeyCode,
+        action: GridAction,
         context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
-        let (next_transition, messages) = self.nested.on_key_pressed(key_code, context);
+        let (next_transition, messages) = self.nested.on_key_pressed(action, context);
 
         if let Transition::ChangeState(new_state) = next_transition {
             self.next(new_state);
@@ -133,9 +133,10 @@ impl Default for LogoCtrl {
 }
 
 impl LogoCtrl {
-    pub fn selecting(point: Point) -> LogoCtrl {
+    // an area selection carried over from another mode
+    pub fn selecting(point: Point, origin_selection: Vec<(usize, usize)>) -> LogoCtrl {
         LogoCtrl {
-            nested: Box::new(Selecting::from_args(point)),
+            nested: Box::new(Selecting::from_args(point, origin_selection)),
         }
     }
 }
@@ -149,7 +150,7 @@ impl WidgetState for Waiting {
         bounds: Rectangle,
         cursor: Point,
         base_pattern: GridPattern,
-        _context: &mut WidgetContext,
+        context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
         let mut grid_messages = vec![
             GridMessage::TrackSelected(get_hovered_track(cursor, bounds)),
@@ -157,23 +158,25 @@ impl WidgetState for Waiting {
         ];
 
         // check if we hover an event on the grid
-        match base_pattern.get_hovered(cursor, bounds) {
+        match base_pattern.get_hovered(cursor, bounds, context.viewport) {
             // if yes remove event
             Some((grid_id, _grid_event)) => {
                 grid_messages.push(GridMessage::Delete(*grid_id));
             },
             // otherwise add event
             None => {
-                let step_width = get_step_width(bounds.size());
+                let content_bounds = context.viewport.get_content_bounds(bounds);
+                let step_width = get_step_width(content_bounds.size());
                 let interactive_area = Rectangle {
-                    x: bounds.x,
-                    y: bounds.y,
-                    width: bounds.width - step_width,
-                    height: bounds.height,
+                    x: content_bounds.x,
+                    y: content_bounds.y,
+                    width: content_bounds.width - step_width,
+                    height: content_bounds.height,
                 };
 
                 if interactive_area.contains(cursor) {
-                    let (step, track, offset) = get_hovered_step(cursor, bounds, true);
+                    let (step, track, offset) =
+                        get_hovered_step(cursor, bounds, context.viewport, true);
                     grid_messages.push(GridMessage::Add((step, track, offset)));
                 }
             }
@@ -187,14 +190,14 @@ impl WidgetState for Waiting {
         bounds: Rectangle,
         cursor: Point,
         base_pattern: GridPattern,
-        _context: &mut WidgetContext,
+        context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
         let mut grid_messages = vec![
             GridMessage::TrackSelected(get_hovered_track(cursor, bounds)),
         ];
 
         // check if we hover an event on the grid
-        match base_pattern.get_hovered(cursor, bounds) {
+        match base_pattern.get_hovered(cursor, bounds, context.viewport) {
             // if yes add event to selection and change the velocity of selected events
             Some(((step, track), _)) => {
                 grid_messages.push(GridMessage::AddOneToSelection((*step, *track)));
@@ -206,7 +209,10 @@ impl WidgetState for Waiting {
             }
             // otherwise change to area selection mode
             None => (
-                Transition::ChangeState(Box::new(Selecting::from_args(cursor))),
+                Transition::ChangeState(Box::new(Selecting::from_args(
+                    cursor,
+                    base_pattern.get_selection(),
+                ))),
                 Some(grid_messages),
             ),
         }
@@ -219,7 +225,7 @@ impl WidgetState for Waiting {
         base_pattern: GridPattern,
         context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
-        match base_pattern.get_hovered(cursor, bounds) {
+        match base_pattern.get_hovered(cursor, bounds, context.viewport) {
             Some((_, _)) => {
                 context.mouse_interaction = mouse::Interaction::ResizingVertically;
             }
@@ -233,20 +239,10 @@ impl WidgetState for Waiting {
 
     fn on_key_pressed(
         &mut self,
-        key_code: keyboard::KeyCode,
-        _context: &mut WidgetContext,
+        action: GridAction,
+        context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
-        let grid_message = match key_code {
-            keyboard::KeyCode::A => Some(vec![GridMessage::EmptySelection(), GridMessage::SelectAll()]),
-            keyboard::KeyCode::Backspace => Some(vec![GridMessage::DeleteSelection()]),
-            keyboard::KeyCode::Left => Some(vec![GridMessage::MoveSelection((-0.05, 0), true)]),
-            keyboard::KeyCode::Up => Some(vec![GridMessage::MoveSelection((0., -1), true)]),
-            keyboard::KeyCode::Right => Some(vec![GridMessage::MoveSelection((0.05, 0), true)]),
-            keyboard::KeyCode::Down => Some(vec![GridMessage::MoveSelection((0., 1), true)]),
-            _ => None,
-        };
-
-        (Transition::DoNothing, grid_message)
+        (Transition::DoNothing, Some(action.messages(context.snap)))
     }
 
     fn on_modifier_change(
@@ -269,10 +265,16 @@ impl WidgetState for Waiting {
 #[derive(Debug, Default)]
 struct Selecting {
     origin: Point,
+    origin_selection: Vec<(usize, usize)>, // the selection before the click, restored on cancel
 }
 
 impl Selecting {
-    fn from_args(point: Point) -> Self { Selecting { origin: point } }
+    fn from_args(point: Point, origin_selection: Vec<(usize, usize)>) -> Self {
+        Selecting {
+            origin: point,
+            origin_selection,
+        }
+    }
 }
 
 impl WidgetState for Selecting {
@@ -309,9 +311,17 @@ impl WidgetState for Selecting {
         // display selection Rectangle
         context.selection_rectangle = Some(selection);
 
+        // the pattern is hit-tested in content coordinates
+        let viewport = context.viewport;
+        let content_selection =
+            viewport.convert_rectangle_to_content_coordinates(selection, bounds.size());
+
         (
             Transition::DoNothing,
-            Some(vec![GridMessage::SelectArea(selection, bounds.size())]),
+            Some(vec![GridMessage::SelectArea(
+                content_selection,
+                viewport.get_content_size(bounds.size()),
+            )]),
         )
     }
 
@@ -328,6 +338,25 @@ impl WidgetState for Selecting {
         (Transition::ChangeState(Box::new(Waiting::default())), None)
     }
 
+    fn on_key_pressed(
+        &mut self,
+        action: GridAction,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        match action {
+            GridAction::Cancel => {
+                // erase selection Rectangle
+                context.selection_rectangle = None;
+
+                (
+                    Transition::ChangeState(Box::new(Waiting::default())),
+                    Some(restore_selection(&self.origin_selection)),
+                )
+            }
+            _ => (Transition::DoNothing, None),
+        }
+    }
+
     fn on_modifier_change(
         &mut self,
         modifiers: keyboard::Modifiers,
@@ -335,7 +364,10 @@ impl WidgetState for Selecting {
     ) -> (Transition, Option<Vec<GridMessage>>) {
         if !modifiers.logo() && !modifiers.control() {
             (
-                Transition::ChangeParentState(Box::new(Idle::selecting(self.origin))),
+                Transition::ChangeParentState(Box::new(Idle::selecting(
+                    self.origin,
+                    self.origin_selection.clone(),
+                ))),
                 None,
             )
         } else {
@@ -347,12 +379,13 @@ impl WidgetState for Selecting {
 #[derive(Debug, Default)]
 struct SetVelocity {
     origin: Point,
-    edit_speed: f32
+    edit_speed: f32,
+    ratio: f32
 }
 
 impl SetVelocity {
     fn from_args(point: Point) -> Self {
-        SetVelocity { origin: point, edit_speed: 2.0 }
+        SetVelocity { origin: point, edit_speed: 2.0, ratio: 0. }
     }
     fn set_edit_speed(&mut self, edit_speed: f32) {
         self.edit_speed = edit_speed;
@@ -370,9 +403,12 @@ impl WidgetState for SetVelocity {
         let ratio = ((self.origin.y - cursor.y) * self.edit_speed).min(127.).max(-127.) / 127.;
         self.origin.y = cursor.y; // reset origin with current cursor position
 
+        // velocities are previewed from the base pattern until the drag is committed
+        self.ratio = (self.ratio + ratio).min(1.).max(-1.);
+
         (
             Transition::DoNothing,
-            Some(vec![GridMessage::SetVelocity(ratio)]),
+            Some(vec![GridMessage::PreviewVelocity(self.ratio)]),
         )
     }
 
@@ -386,7 +422,28 @@ impl WidgetState for SetVelocity {
         // erase selection Rectangle
         context.selection_rectangle = None;
 
-        (Transition::ChangeState(Box::new(Waiting::default())), None)
+        (
+            Transition::ChangeState(Box::new(Waiting::default())),
+            Some(vec![GridMessage::CommitState()]),
+        )
+    }
+
+    fn on_key_pressed(
+        &mut self,
+        action: GridAction,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        match action {
+            GridAction::Cancel => {
+                context.mouse_interaction = mouse::Interaction::default();
+
+                (
+                    Transition::ChangeState(Box::new(Waiting::default())),
+                    Some(vec![GridMessage::DiscardState()]),
+                )
+            }
+            _ => (Transition::DoNothing, None),
+        }
     }
 
     fn on_modifier_change(
@@ -402,7 +459,7 @@ impl WidgetState for SetVelocity {
             context.mouse_interaction = mouse::Interaction::default();
             (
                 Transition::ChangeParentState(Box::new(Idle::default())),
-                None,
+                Some(vec![GridMessage::CommitState()]),
             )
         } else {
             self.set_edit_speed(2.0);
@@ -6,7 +6,7 @@ pub use idle::Idle;
 pub use logoctrl::LogoCtrl;
 pub use shift::Shift;
 
-use super::WidgetContext;
+use super::{GridAction, WidgetContext};
 use crate::core::grid::{GridMessage,GridPattern};
 use iced_native::{keyboard, Point, Rectangle};
 use std::fmt::Debug;
@@ -62,7 +62,15 @@ pub trait WidgetState: Debug {
 
     fn on_key_pressed(
         &mut self,
-        _key_code: keyboard::KeyCode,
+        _action: GridAction,
+        _context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        (Transition::DoNothing, None)
+    }
+
+    // the selection was dragged out of the grid and released elsewhere
+    fn on_dropped_outside(
+        &mut self,
         _context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
         (Transition::DoNothing, None)
@@ -87,6 +95,17 @@ pub trait WidgetState: Debug {
     fn next(&mut self, _next_state: Box<dyn WidgetState + Send>) {}
 }
 
+// messages putting back the selection saved when an area selection started
+fn restore_selection(selection: &[(usize, usize)]) -> Vec<GridMessage> {
+    let mut grid_messages = vec![GridMessage::EmptySelection()];
+
+    selection.iter().for_each(|grid_id| {
+        grid_messages.push(GridMessage::AddOneToSelection(*grid_id));
+    });
+
+    grid_messages
+}
+
 #[derive(Debug)]
 pub enum Transition {
     ChangeState(Box<dyn WidgetState + Send>),
@@ -1,6 +1,6 @@
 use super::Idle;
 use super::LogoCtrl;
-use super::{Transition, WidgetContext, WidgetState};
+use super::{restore_selection, GridAction, Transition, WidgetContext, WidgetState};
 use crate::core::grid::{
     get_hovered_track, get_hovered_step, get_step_width,
     GridMessage, GridPattern
@@ -91,26 +91,26 @@ impl WidgetState for Shift {
         modifiers: keyboard::Modifiers,
         context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
-        let transition: Transition = self.nested.on_modifier_change(modifiers, context).0;
+        let (transition, messages) = self.nested.on_modifier_change(modifiers, context);
 
         match transition {
             Transition::ChangeState(new_state) => {
                 self.next(new_state);
-                return (Transition::DoNothing, None);
+                return (Transition::DoNothing, messages);
             }
             Transition::ChangeParentState(new_state) => {
-                return (Transition::ChangeState(new_state), None)
+                return (Transition::ChangeState(new_state), messages)
             }
-            Transition::DoNothing => return (Transition::DoNothing, None),
+            Transition::DoNothing => return (Transition::DoNothing, messages),
         }
     }
 
     fn on_key_pressed(
         &mut self,
-        key_code: keyboard::KeyCode,
+        action: GridAction,
         context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
-        let (next_transition, messages) = self.nested.on_key_pressed(key_code, context);
+        let (next_transition, messages) = self.nested.on_key_pressed(action, context);
 
         if let Transition::ChangeState(new_state) = next_transition {
             self.next(new_state);
@@ -146,30 +146,31 @@ impl WidgetState for Waiting {
         bounds: Rectangle,
         cursor: Point,
         base_pattern: GridPattern,
-        _context: &mut WidgetContext,
+        context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
         let mut grid_messages = vec![
             GridMessage::TrackSelected(get_hovered_track(cursor, bounds)),
         ];
 
         // check if we hover an event on the grid
-        match base_pattern.get_hovered(cursor, bounds) {
+        match base_pattern.get_hovered(cursor, bounds, context.viewport) {
             // if yes toggle select flag for event
             Some(((step, track), _)) => {
                 grid_messages.push(GridMessage::ToggleOne((*step, *track)));
             },
             // otherwise add event
             None => {
-                let step_width = get_step_width(bounds.size());
+                let content_bounds = context.viewport.get_content_bounds(bounds);
+                let step_width = get_step_width(content_bounds.size());
                 let interactive_area = Rectangle {
-                    x: bounds.x + step_width,
-                    y: bounds.y,
-                    width: bounds.width - 2. * step_width,
-                    height: bounds.height,
+                    x: content_bounds.x + step_width,
+                    y: content_bounds.y,
+                    width: content_bounds.width - 2. * step_width,
+                    height: content_bounds.height,
                 };
 
                 if interactive_area.contains(cursor) {
-                    let (step, track, _) = get_hovered_step(cursor, bounds, true);
+                    let (step, track, _) = get_hovered_step(cursor, bounds, context.viewport, true);
                     grid_messages.push(GridMessage::Add((step, track, 0.)));
                 }
             }
@@ -183,14 +184,14 @@ impl WidgetState for Waiting {
         bounds: Rectangle,
         cursor: Point,
         base_pattern: GridPattern,
-        _context: &mut WidgetContext,
+        context: &mut WidgetContext,
     ) -> (Transition, Option<Vec<GridMessage>>) {
         let mut grid_messages = vec![
             GridMessage::TrackSelected(get_hovered_track(cursor, bounds)),
         ];
 
         // check if we hover an event on the grid
-        match base_pattern.get_hovered(cursor, bounds) {
+        match base_pattern.get_hovered(cursor, bounds, context.viewport) {
             // if yes toggle selection of this specific event
             Some(((step, track), _)) => {
                 grid_messages.push(GridMessage::ToggleOne((*step, *track)));
@@ -199,7 +200,10 @@ impl WidgetState for Waiting {
             },
             // otherwise change to area selection mode
             None => (
-                Transition::ChangeState(Box::new(Selecting::from_args(cursor))),
+                Transition::ChangeState(Box::new(Selecting::from_args(
+                    cursor,
+                    base_pattern.get_selection(),
+                ))),
                 Some(grid_messages),
             ),
         }
@@ -229,10 +233,16 @@ impl WidgetState for Waiting {
 #[derive(Debug, Default)]
 struct Selecting {
     origin: Point,
+    origin_selection: Vec<(usize, usize)>, // the selection before the click, restored on cancel
 }
 
 impl Selecting {
-    fn from_args(point: Point) -> Self { Selecting { origin: point } }
+    fn from_args(point: Point, origin_selection: Vec<(usize, usize)>) -> Self {
+        Selecting {
+            origin: point,
+            origin_selection,
+        }
+    }
 }
 
 impl WidgetState for Selecting {
@@ -269,9 +279,17 @@ impl WidgetState for Selecting {
         // display selection Rectangle
         context.selection_rectangle = Some(selection);
 
+        // the pattern is hit-tested in content coordinates
+        let viewport = context.viewport;
+        let content_selection =
+            viewport.convert_rectangle_to_content_coordinates(selection, bounds.size());
+
         (
             Transition::DoNothing,
-            Some(vec![GridMessage::AddSelectedArea(selection, bounds.size())]),
+            Some(vec![GridMessage::AddSelectedArea(
+                content_selection,
+                viewport.get_content_size(bounds.size()),
+            )]),
         )
     }
 
@@ -288,6 +306,25 @@ impl WidgetState for Selecting {
         (Transition::ChangeState(Box::new(Waiting::default())), None)
     }
 
+    fn on_key_pressed(
+        &mut self,
+        action: GridAction,
+        context: &mut WidgetContext,
+    ) -> (Transition, Option<Vec<GridMessage>>) {
+        match action {
+            GridAction::Cancel => {
+                // erase selection Rectangle
+                context.selection_rectangle = None;
+
+                (
+                    Transition::ChangeState(Box::new(Waiting::default())),
+                    Some(restore_selection(&self.origin_selection)),
+                )
+            }
+            _ => (Transition::DoNothing, None),
+        }
+    }
+
     fn on_modifier_change(
         &mut self,
         modifiers: keyboard::Modifiers,
@@ -295,12 +332,18 @@ impl WidgetState for Selecting {
     ) -> (Transition, Option<Vec<GridMessage>>) {
         if modifiers.logo() || modifiers.control() {
             (
-                Transition::ChangeParentState(Box::new(LogoCtrl::selecting(self.origin))),
+                Transition::ChangeParentState(Box::new(LogoCtrl::selecting(
+                    self.origin,
+                    self.origin_selection.clone(),
+                ))),
                 None,
             )
         } else if !modifiers.shift() && !modifiers.logo() && !modifiers.control() {
             (
-                Transition::ChangeParentState(Box::new(Idle::selecting(self.origin))),
+                Transition::ChangeParentState(Box::new(Idle::selecting(
+                    self.origin,
+                    self.origin_selection.clone(),
+                ))),
                 None,
             )
         } else {
@@ -0,0 +1,440 @@
+//! Draws the grid with quads and text only, so it works with any renderer
+//! implementing `iced_native::text::Renderer`.
+//!
+//! Nothing is cached, the whole pattern is drawn on every frame. A custom
+//! renderer only has to forward its `grid::Renderer` methods here:
+//!
+//! ```ignore
+//! impl grid::Renderer for MyRenderer {
+//!     type Style = Box<dyn iced_sequencing::style::grid::StyleSheet>;
+//!
+//!     fn draw(&mut self, bounds: Rectangle, /* .. */ style: &Self::Style, _layers: &Layers) {
+//!         grid::quad::draw(self, bounds, /* .. */ style.as_ref())
+//!     }
+//!     // same for draw_context_menu, draw_tooltip and event_inset
+//! }
+//! ```
+use std::time::Duration;
+
+use iced_native::{
+    alignment, mouse, renderer, text, Background, Color, Point, Rectangle, Vector,
+};
+
+use ganic_no_std::NUM_PERCS;
+use crate::core::grid::{get_step_dimensions, GridPattern, Snap, Viewport};
+use crate::style::grid::{Stroke, Style, StyleSheet};
+
+use super::context_menu::{get_item_bounds, MenuAction, MENU_PADDING};
+use super::geometry::{self, Shape};
+use super::tooltip::{TOOLTIP_LINE_HEIGHT, TOOLTIP_PADDING};
+use super::{Layers, Status};
+
+pub fn draw<Renderer: text::Renderer>(
+    renderer: &mut Renderer,
+    bounds: Rectangle,
+    drawable_area: Rectangle,
+    cursor_position: Point,
+    grid_pattern: &GridPattern,
+    ghost_pattern: Option<&GridPattern>,
+    viewport: Viewport,
+    snap: Snap,
+    status: Status,
+    focused_track: Option<usize>,
+    selection: Option<Rectangle>,
+    is_deselecting: bool,
+    velocity_readout: Option<f32>,
+    is_playing: bool,
+    highlight: [usize; NUM_PERCS],
+    playhead: Option<f32>,
+    flashes: [Option<(usize, Duration)>; NUM_PERCS],
+    mutes: [bool; NUM_PERCS],
+    is_focused: bool,
+    style_sheet: &dyn StyleSheet,
+) {
+    let style = match status {
+        Status::Disabled => style_sheet.disabled(),
+        Status::Dragging => style_sheet.dragging_selection(),
+        Status::Focused => style_sheet.focused(),
+        Status::Hovered => style_sheet.hovered(),
+        Status::Active => style_sheet.default(),
+    };
+
+    let content_size = viewport.get_content_size(drawable_area.size());
+    let scroll_offset = viewport.get_scroll_offset(drawable_area.size());
+    let step_size = get_step_dimensions(content_size);
+
+    if let Some(background) = style.background {
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds,
+                border_radius: background.border_radius,
+                border_width: background.border_width,
+                border_color: background.border_color,
+            },
+            Background::Color(background.bg_color),
+        );
+    }
+
+    // steps scrolled out of the drawable area are clipped
+    renderer.with_layer(drawable_area, |renderer| {
+        renderer.with_translation(
+            Vector::new(drawable_area.x - scroll_offset, drawable_area.y),
+            |renderer| {
+                let grid = geometry::grid(content_size, step_size, snap, focused_track, &style);
+                draw_shapes(renderer, &grid);
+
+                // the played events are drawn once, with the highlight
+                let played = if is_playing { Some(highlight) } else { None };
+                draw_shapes(
+                    renderer,
+                    &geometry::steps(content_size, grid_pattern, None, step_size, played, mutes, &style),
+                );
+
+                if let (Some(ghost_pattern), Some(ghost_stroke)) = (ghost_pattern, style.ghost_stroke) {
+                    draw_shapes(
+                        renderer,
+                        &geometry::ghosts(content_size, ghost_pattern, step_size, ghost_stroke),
+                    );
+                }
+
+                if is_playing {
+                    if style.highlight_current_step {
+                        draw_shapes(
+                            renderer,
+                            &geometry::highlight(content_size, highlight, mutes, &style),
+                        );
+                    }
+
+                    draw_shapes(
+                        renderer,
+                        &geometry::played_steps(
+                            content_size,
+                            grid_pattern,
+                            step_size,
+                            highlight,
+                            mutes,
+                            &style,
+                        ),
+                    );
+
+                    if let Some(flash) = style.event.flash {
+                        draw_shapes(
+                            renderer,
+                            &geometry::flashes(
+                                content_size,
+                                grid_pattern,
+                                step_size,
+                                flashes,
+                                mutes,
+                                flash,
+                            ),
+                        );
+                    }
+                }
+
+                if let (true, Some(position), Some(stroke)) = (is_playing, playhead, style.playhead) {
+                    draw_shapes(renderer, &[geometry::playhead(content_size, position, stroke)]);
+                }
+            },
+        );
+
+        if let Some(selection) = selection {
+            renderer.with_translation(
+                Vector::new(drawable_area.x, drawable_area.y),
+                |renderer| {
+                    draw_shapes(renderer, &geometry::selection(selection, is_deselecting, &style))
+                },
+            );
+        }
+    });
+
+    if let Some(velocity) = velocity_readout {
+        let readout_bounds = geometry::get_readout_bounds(cursor_position);
+
+        renderer.with_layer(readout_bounds, |renderer| {
+            draw_readout(renderer, readout_bounds, &format!("{}%", (velocity * 100.).round()), &style)
+        });
+    }
+
+    // focus ring, on its own layer to stay above the steps
+    if let (true, Some(focus_ring)) = (is_focused, style.focus_ring) {
+        let border_radius = style
+            .background
+            .map_or(0., |background| background.border_radius);
+
+        renderer.with_layer(bounds, |renderer| {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds,
+                    border_radius,
+                    border_width: focus_ring.line_width,
+                    border_color: focus_ring.color,
+                },
+                Background::Color(Color::TRANSPARENT),
+            )
+        });
+    }
+}
+
+pub fn draw_context_menu<Renderer: text::Renderer>(
+    renderer: &mut Renderer,
+    bounds: Rectangle,
+    actions: &[MenuAction],
+    hovered: Option<usize>,
+    style_sheet: &dyn StyleSheet,
+) {
+    let style = style_sheet.default().context_menu;
+
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds,
+            border_radius: style.background.border_radius,
+            border_width: style.background.border_width,
+            border_color: style.background.border_color,
+        },
+        Background::Color(style.background.bg_color),
+    );
+
+    for (index, action) in actions.iter().enumerate() {
+        let item_bounds = get_item_bounds(bounds, index);
+
+        let text_color = if hovered == Some(index) {
+            fill_rectangle(renderer, item_bounds, style.hovered_bg_color);
+
+            style.hovered_text_color
+        } else {
+            style.text_color
+        };
+
+        renderer.fill_text(text::Text {
+            content: &action.label(),
+            bounds: Rectangle {
+                x: item_bounds.x + 2. * MENU_PADDING,
+                y: item_bounds.center_y(),
+                width: item_bounds.width - 4. * MENU_PADDING,
+                ..item_bounds
+            },
+            color: text_color,
+            size: style.text_size,
+            font: Default::default(),
+            horizontal_alignment: alignment::Horizontal::Left,
+            vertical_alignment: alignment::Vertical::Center,
+        });
+    }
+}
+
+pub fn draw_tooltip<Renderer: text::Renderer>(
+    renderer: &mut Renderer,
+    bounds: Rectangle,
+    lines: &[String],
+    style_sheet: &dyn StyleSheet,
+) {
+    let style = style_sheet.default().tooltip;
+
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds,
+            border_radius: style.background.border_radius,
+            border_width: style.background.border_width,
+            border_color: style.background.border_color,
+        },
+        Background::Color(style.background.bg_color),
+    );
+
+    for (index, line) in lines.iter().enumerate() {
+        renderer.fill_text(text::Text {
+            content: line,
+            bounds: Rectangle {
+                x: bounds.x + 2. * TOOLTIP_PADDING,
+                y: bounds.y + TOOLTIP_PADDING + (index as f32 + 0.5) * TOOLTIP_LINE_HEIGHT,
+                width: bounds.width - 4. * TOOLTIP_PADDING,
+                height: TOOLTIP_LINE_HEIGHT,
+            },
+            color: style.text_color,
+            size: style.text_size,
+            font: Default::default(),
+            horizontal_alignment: alignment::Horizontal::Left,
+            vertical_alignment: alignment::Vertical::Center,
+        });
+    }
+}
+
+/// The grid with `iced_native`'s null renderer, e.g. to test widget events.
+impl super::Renderer for renderer::Null {
+    type Style = Box<dyn StyleSheet>;
+
+    fn draw(
+        &mut self,
+        bounds: Rectangle,
+        drawable_area: Rectangle,
+        cursor_position: Point,
+        grid_pattern: &GridPattern,
+        ghost_pattern: Option<&GridPattern>,
+        viewport: Viewport,
+        snap: Snap,
+        status: Status,
+        focused_track: Option<usize>,
+        selection: Option<Rectangle>,
+        is_deselecting: bool,
+        velocity_readout: Option<f32>,
+        _mouse_interaction: mouse::Interaction,
+        is_playing: bool,
+        highlight: [usize; NUM_PERCS],
+        playhead: Option<f32>,
+        flashes: [Option<(usize, Duration)>; NUM_PERCS],
+        mutes: [bool; NUM_PERCS],
+        is_focused: bool,
+        style: &Self::Style,
+        _layers: &Layers,
+    ) {
+        draw(
+            self,
+            bounds,
+            drawable_area,
+            cursor_position,
+            grid_pattern,
+            ghost_pattern,
+            viewport,
+            snap,
+            status,
+            focused_track,
+            selection,
+            is_deselecting,
+            velocity_readout,
+            is_playing,
+            highlight,
+            playhead,
+            flashes,
+            mutes,
+            is_focused,
+            style.as_ref(),
+        )
+    }
+
+    fn draw_context_menu(
+        &mut self,
+        bounds: Rectangle,
+        actions: &[MenuAction],
+        hovered: Option<usize>,
+        style: &Self::Style,
+    ) {
+        draw_context_menu(self, bounds, actions, hovered, style.as_ref())
+    }
+
+    fn draw_tooltip(&mut self, bounds: Rectangle, lines: &[String], style: &Self::Style) {
+        draw_tooltip(self, bounds, lines, style.as_ref())
+    }
+
+    fn event_inset(&self, style: &Self::Style) -> f32 {
+        style.default().event.contour_width
+    }
+}
+
+fn fill_rectangle<Renderer: iced_native::Renderer>(
+    renderer: &mut Renderer,
+    bounds: Rectangle,
+    color: Color,
+) {
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds,
+            border_radius: 0.,
+            border_width: 0.,
+            border_color: Color::TRANSPARENT,
+        },
+        Background::Color(color),
+    );
+}
+
+fn stroke_rectangle<Renderer: iced_native::Renderer>(
+    renderer: &mut Renderer,
+    bounds: Rectangle,
+    stroke: Stroke,
+) {
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds,
+            border_radius: 0.,
+            border_width: stroke.line_width,
+            border_color: stroke.color,
+        },
+        Background::Color(Color::TRANSPARENT),
+    );
+}
+
+// a line from the top of the content, centered on x
+fn fill_vertical_line<Renderer: iced_native::Renderer>(
+    renderer: &mut Renderer,
+    x: f32,
+    height: f32,
+    stroke: Stroke,
+) {
+    fill_rectangle(
+        renderer,
+        Rectangle {
+            x: x - stroke.line_width / 2.,
+            y: 0.,
+            width: stroke.line_width,
+            height,
+        },
+        stroke.color,
+    );
+}
+
+fn draw_readout<Renderer: text::Renderer>(
+    renderer: &mut Renderer,
+    bounds: Rectangle,
+    content: &str,
+    style: &Style,
+) {
+    let readout = style.readout;
+
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds,
+            border_radius: readout.background.border_radius,
+            border_width: readout.background.border_width,
+            border_color: readout.background.border_color,
+        },
+        Background::Color(readout.background.bg_color),
+    );
+
+    renderer.fill_text(text::Text {
+        content,
+        bounds: Rectangle {
+            x: bounds.center_x(),
+            y: bounds.center_y(),
+            ..bounds
+        },
+        color: readout.text_color,
+        size: readout.text_size,
+        font: Default::default(),
+        horizontal_alignment: alignment::Horizontal::Center,
+        vertical_alignment: alignment::Vertical::Center,
+    });
+}
+
+fn draw_shapes<Renderer: text::Renderer>(renderer: &mut Renderer, shapes: &[Shape]) {
+    for shape in shapes {
+        match shape {
+            Shape::Fill(bounds, color) => fill_rectangle(renderer, *bounds, *color),
+            Shape::Outline(bounds, stroke) => stroke_rectangle(renderer, *bounds, *stroke),
+            Shape::VerticalLine { x, height, stroke } => {
+                fill_vertical_line(renderer, *x, *height, *stroke)
+            }
+            Shape::Label { content, bounds, color, size } => renderer.fill_text(text::Text {
+                content,
+                bounds: Rectangle {
+                    x: bounds.center_x(),
+                    y: bounds.center_y(),
+                    ..*bounds
+                },
+                color: *color,
+                size: *size,
+                font: Default::default(),
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+            }),
+        }
+    }
+}
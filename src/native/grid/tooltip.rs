@@ -0,0 +1,89 @@
+use std::time::Duration;
+use iced_native::{layout, renderer, Layout, Point, Size};
+
+use crate::core::grid::{get_step_duration, GridEvent};
+use ganic_no_std::NUM_PERCS;
+
+pub const TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+pub const TOOLTIP_WIDTH: f32 = 180.;
+pub const TOOLTIP_LINE_HEIGHT: f32 = 18.;
+pub const TOOLTIP_PADDING: f32 = 6.;
+pub const TOOLTIP_MARGIN: f32 = 12.; // between the cursor and the tooltip
+
+#[derive(Debug, Clone, Copy)]
+pub struct Tooltip {
+    pub position: Point,
+    pub step: usize,
+    pub track: usize, // grid track
+    pub event: GridEvent,
+    pub bpm: f32,
+}
+
+impl Tooltip {
+    pub fn lines(&self) -> Vec<String> {
+        let offset_ms = self.event.offset * get_step_duration(self.bpm);
+
+        // steps and tracks are counted from 1, tracks like the pattern
+        vec![
+            format!("Step {}, track {}", self.step + 1, NUM_PERCS - self.track),
+            format!(
+                "Velocity {} ({}%)",
+                (self.event.velocity * 127.).round(),
+                (self.event.velocity * 100.).round()
+            ),
+            format!("Offset {:+.2} step ({:+.0} ms)", self.event.offset, offset_ms),
+        ]
+    }
+}
+
+pub fn get_tooltip_size(line_count: usize) -> Size {
+    Size {
+        width: TOOLTIP_WIDTH,
+        height: line_count as f32 * TOOLTIP_LINE_HEIGHT + 2. * TOOLTIP_PADDING,
+    }
+}
+
+pub struct TooltipOverlay<'a, Renderer: super::Renderer> {
+    tooltip: Tooltip,
+    style: &'a Renderer::Style,
+}
+
+impl<'a, Renderer: super::Renderer> TooltipOverlay<'a, Renderer> {
+    pub fn new(tooltip: Tooltip, style: &'a Renderer::Style) -> Self {
+        TooltipOverlay { tooltip, style }
+    }
+}
+
+impl<'a, Message, Renderer> iced_native::overlay::Overlay<Message, Renderer>
+    for TooltipOverlay<'a, Renderer>
+where
+    Renderer: super::Renderer,
+{
+    fn layout(&self, _renderer: &Renderer, bounds: Size, position: Point) -> layout::Node {
+        let size = get_tooltip_size(self.tooltip.lines().len());
+
+        // below right of the cursor, flipped when it would leave the window
+        let x = match position.x + TOOLTIP_MARGIN + size.width > bounds.width {
+            true => position.x - TOOLTIP_MARGIN - size.width,
+            false => position.x + TOOLTIP_MARGIN,
+        };
+        let y = match position.y + TOOLTIP_MARGIN + size.height > bounds.height {
+            true => position.y - TOOLTIP_MARGIN - size.height,
+            false => position.y + TOOLTIP_MARGIN,
+        };
+
+        let mut node = layout::Node::new(size);
+        node.move_to(Point::new(x.max(0.), y.max(0.)));
+        node
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+    ) {
+        renderer.draw_tooltip(layout.bounds(), &self.tooltip.lines(), self.style);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod grid;
 pub mod h_list;
 pub mod multi_slider;
+pub mod ruler;
 pub mod snapshot_view;
+pub mod track_header;
@@ -0,0 +1,455 @@
+use iced_native::{
+    alignment, event, layout, mouse, renderer, text, Background, Clipboard, Color, Element,
+    Event, Layout, Length, Padding, Point, Rectangle, Shell, Size, Widget,
+};
+
+use crate::core::grid::{
+    get_hovered_position, get_step_position, get_step_width, Viewport, BEATS, STEPS_PER_BEAT,
+};
+pub use crate::style::ruler::{Style, StyleSheet};
+use ganic_no_std::NUM_STEPS;
+
+// below this width only the beat starts are numbered
+const MIN_LABEL_WIDTH: f32 = 16.;
+
+#[derive(Debug, Clone, Copy)]
+pub enum RulerMessage {
+    Seek(f32), // position in steps
+    LoopChanged((usize, usize)), // (start, end), end excluded
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    Seek,
+    LoopStart(usize), // the end stays in place
+    LoopEnd(usize), // the start stays in place
+    LoopRegion { origin: f32, region: (usize, usize) },
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    drag: Option<Drag>,
+}
+
+impl State {
+    pub fn new() -> State {
+        State::default()
+    }
+}
+
+/// Step numbers, playhead and loop region drawn above a [`Grid`] of the same width.
+///
+/// [`Grid`]: crate::native::grid::Grid
+#[allow(missing_debug_implementations)]
+pub struct Ruler<'a, Message> {
+    state: &'a mut State,
+    playhead: Option<f32>,
+    loop_region: Option<(usize, usize)>,
+    on_event: Box<dyn Fn(RulerMessage) -> Message>,
+    viewport: Viewport,
+    width: Length,
+    height: Length,
+    padding: Padding,
+    style_sheet: Box<dyn StyleSheet + 'a>,
+}
+
+impl<'a, Message> Ruler<'a, Message> {
+    pub fn new<F>(state: &'a mut State, on_event: F) -> Self
+    where
+        F: 'static + Fn(RulerMessage) -> Message,
+    {
+        Ruler {
+            state,
+            playhead: None,
+            loop_region: None,
+            on_event: Box::new(on_event),
+            viewport: Viewport::default(),
+            width: Length::Fill,
+            height: Length::Units(24),
+            padding: Padding::ZERO,
+            style_sheet: Default::default(),
+        }
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    // use the grid padding to keep the columns aligned
+    pub fn padding<P: Into<Padding>>(mut self, padding: P) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    pub fn playhead(mut self, position: f32) -> Self {
+        self.playhead = Some(position);
+        self
+    }
+
+    pub fn loop_region(mut self, region: (usize, usize)) -> Self {
+        self.loop_region = Some(region);
+        self
+    }
+
+    // pass the grid viewport to follow its zoom and scroll
+    pub fn viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn style(mut self, style: impl Into<Box<dyn StyleSheet + 'a>>) -> Self {
+        self.style_sheet = style.into();
+        self
+    }
+
+    fn get_loop_lane(&self, bounds: Rectangle) -> Rectangle {
+        Rectangle {
+            height: bounds.height / 2.,
+            ..bounds
+        }
+    }
+
+    fn get_handle_bounds(&self, bounds: Rectangle, step: usize, handle_width: f32) -> Rectangle {
+        let loop_lane = self.get_loop_lane(bounds);
+
+        Rectangle {
+            x: get_step_position(step as f32, bounds, self.viewport) - handle_width / 2.,
+            width: handle_width,
+            ..loop_lane
+        }
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for Ruler<'a, Message>
+where
+    Renderer: text::Renderer,
+{
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits
+            .width(self.width)
+            .height(self.height)
+            .pad(self.padding);
+
+        let mut content = layout::Node::new(limits.resolve(Size::ZERO));
+        content.move_to(Point::new(
+            self.padding.left.into(),
+            self.padding.top.into(),
+        ));
+
+        let size = limits.resolve(content.size()).pad(self.padding);
+
+        layout::Node::with_children(size, vec![content])
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let bounds = layout.children().next().unwrap().bounds();
+        let position = get_hovered_position(cursor_position, bounds, self.viewport);
+        let handle_width = self.style_sheet.default().loop_handle_width;
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if !bounds.contains(cursor_position) {
+                    return event::Status::Ignored;
+                }
+
+                let drag = match self.get_loop_lane(bounds).contains(cursor_position) {
+                    true => match self.loop_region {
+                        Some((start, end))
+                            if self
+                                .get_handle_bounds(bounds, start, handle_width)
+                                .contains(cursor_position) =>
+                        {
+                            Drag::LoopStart(end)
+                        }
+                        Some((start, end))
+                            if self
+                                .get_handle_bounds(bounds, end, handle_width)
+                                .contains(cursor_position) =>
+                        {
+                            Drag::LoopEnd(start)
+                        }
+                        Some((start, end))
+                            if position >= start as f32 && position < end as f32 =>
+                        {
+                            Drag::LoopRegion {
+                                origin: position,
+                                region: (start, end),
+                            }
+                        }
+                        // a new one step region grows from the clicked step
+                        _ => {
+                            let start = (position.floor() as usize).min(NUM_STEPS - 1);
+                            shell.publish((self.on_event)(RulerMessage::LoopChanged((
+                                start,
+                                start + 1,
+                            ))));
+
+                            Drag::LoopEnd(start)
+                        }
+                    },
+                    false => {
+                        shell.publish((self.on_event)(RulerMessage::Seek(position)));
+
+                        Drag::Seek
+                    }
+                };
+
+                self.state.drag = Some(drag);
+
+                return event::Status::Captured;
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let message = match self.state.drag {
+                    Some(Drag::Seek) => RulerMessage::Seek(position),
+                    Some(Drag::LoopStart(end)) => {
+                        let start = (position.round() as usize).min(end - 1);
+                        RulerMessage::LoopChanged((start, end))
+                    }
+                    Some(Drag::LoopEnd(start)) => {
+                        let end = (position.round() as usize).max(start + 1).min(NUM_STEPS);
+                        RulerMessage::LoopChanged((start, end))
+                    }
+                    Some(Drag::LoopRegion { origin, region }) => {
+                        let offset = (position - origin).round() as isize;
+                        let offset = offset
+                            .max(-(region.0 as isize))
+                            .min((NUM_STEPS - region.1) as isize);
+
+                        RulerMessage::LoopChanged((
+                            (region.0 as isize + offset) as usize,
+                            (region.1 as isize + offset) as usize,
+                        ))
+                    }
+                    None => return event::Status::Ignored,
+                };
+
+                shell.publish((self.on_event)(message));
+
+                return event::Status::Captured;
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                if self.state.drag.take().is_some() {
+                    return event::Status::Captured;
+                }
+            }
+            _ => {}
+        }
+
+        event::Status::Ignored
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.children().next().unwrap().bounds();
+        let style = self.style_sheet.default();
+        let step_width = get_step_width(self.viewport.get_content_size(bounds.size()));
+        let get_x = |position: f32| get_step_position(position, bounds, self.viewport);
+
+        if let Some(background) = style.background {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: layout.bounds(),
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                background,
+            );
+        }
+
+        // steps scrolled out of the ruler are clipped, like in the grid
+        renderer.with_layer(bounds, |renderer| {
+            let fill = |renderer: &mut Renderer, bounds: Rectangle, color: Color| {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds,
+                        border_radius: 0.0,
+                        border_width: 0.0,
+                        border_color: Color::TRANSPARENT,
+                    },
+                    Background::Color(color),
+                );
+            };
+
+            // edge steps & beats
+            fill(
+                renderer,
+                Rectangle { x: get_x(-1.), width: step_width, ..bounds },
+                style.edge_step_bg_color,
+            );
+            fill(
+                renderer,
+                Rectangle { x: get_x(NUM_STEPS as f32), width: step_width, ..bounds },
+                style.edge_step_bg_color,
+            );
+
+            for beat in 0..BEATS {
+                let bg_color = match beat {
+                    0 | 2 => style.even_beat_bg_color,
+                    _ => style.odd_beat_bg_color,
+                };
+
+                fill(
+                    renderer,
+                    Rectangle {
+                        x: get_x((beat * STEPS_PER_BEAT) as f32),
+                        width: step_width * STEPS_PER_BEAT as f32,
+                        ..bounds
+                    },
+                    bg_color,
+                );
+            }
+
+            // loop region
+            if let Some((start, end)) = self.loop_region {
+                let loop_lane = self.get_loop_lane(bounds);
+
+                fill(
+                    renderer,
+                    Rectangle {
+                        x: get_x(start as f32),
+                        width: get_x(end as f32) - get_x(start as f32),
+                        ..loop_lane
+                    },
+                    style.loop_region_color,
+                );
+
+                for step in [start, end] {
+                    let handle_bounds =
+                        self.get_handle_bounds(bounds, step, style.loop_handle_width);
+                    let handle_color = match handle_bounds.contains(cursor_position) {
+                        true => self.style_sheet.hovered_handle(),
+                        false => style.loop_handle_color,
+                    };
+
+                    fill(renderer, handle_bounds, handle_color);
+                }
+            }
+
+            // ticks & step numbers
+            for step in 0..=NUM_STEPS {
+                let is_beat_start = step % STEPS_PER_BEAT == 0;
+                let tick_height = match is_beat_start {
+                    true => bounds.height,
+                    false => bounds.height / 2.,
+                };
+
+                fill(
+                    renderer,
+                    Rectangle {
+                        x: get_x(step as f32),
+                        y: bounds.y + bounds.height - tick_height,
+                        width: 1.,
+                        height: tick_height,
+                    },
+                    style.tick_color,
+                );
+
+                if step < NUM_STEPS && (is_beat_start || step_width >= MIN_LABEL_WIDTH) {
+                    let step_bounds = Rectangle {
+                        x: get_x(step as f32),
+                        y: bounds.y + bounds.height / 2.,
+                        width: step_width,
+                        height: bounds.height / 2.,
+                    };
+
+                    renderer.fill_text(text::Text {
+                        content: &(step + 1).to_string(),
+                        bounds: Rectangle {
+                            x: step_bounds.center_x(),
+                            y: step_bounds.center_y(),
+                            ..step_bounds
+                        },
+                        size: style.text_size,
+                        color: match is_beat_start {
+                            true => style.beat_text_color,
+                            false => style.text_color,
+                        },
+                        font: Default::default(),
+                        horizontal_alignment: alignment::Horizontal::Center,
+                        vertical_alignment: alignment::Vertical::Center,
+                    });
+                }
+            }
+
+            // playhead
+            if let Some(position) = self.playhead {
+                fill(
+                    renderer,
+                    Rectangle {
+                        x: get_x(position) - style.playhead_width / 2.,
+                        width: style.playhead_width,
+                        ..bounds
+                    },
+                    style.playhead_color,
+                );
+            }
+        });
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let bounds = layout.children().next().unwrap().bounds();
+        let handle_width = self.style_sheet.default().loop_handle_width;
+
+        let is_on_handle = match self.loop_region {
+            Some((start, end)) => [start, end].into_iter().any(|step| {
+                self.get_handle_bounds(bounds, step, handle_width)
+                    .contains(cursor_position)
+            }),
+            None => false,
+        };
+
+        match self.state.drag {
+            Some(Drag::LoopRegion { .. }) => mouse::Interaction::Grabbing,
+            Some(Drag::LoopStart(_)) | Some(Drag::LoopEnd(_)) => {
+                mouse::Interaction::ResizingHorizontally
+            }
+            None if is_on_handle => mouse::Interaction::ResizingHorizontally,
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
+
+impl<'a, Message, Renderer> From<Ruler<'a, Message>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'a + text::Renderer,
+{
+    fn from(ruler: Ruler<'a, Message>) -> Element<'a, Message, Renderer> {
+        Element::new(ruler)
+    }
+}
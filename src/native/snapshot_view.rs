@@ -1,5 +1,5 @@
 use crate::core::{
-    grid::{GridEvent, GridPattern},
+    grid::{DragMessage, GridEvent, GridPattern},
     utils::get_step_dimension,
 };
 pub use crate::style::snapshot::{Style, StyleSheet};
@@ -10,12 +10,28 @@ use iced_native::{
     Padding, Point, Rectangle, Shell, Size, Widget,
 };
 
+// how far the cursor travels before a press becomes a drag
+const DRAG_THRESHOLD: f32 = 4.;
+
 pub enum SelectionState {
     Selected(),
     NotSelected(),
     Dirty(),
 }
 
+/// The press a [`DraggableSnapshotView`] drag may start from.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct State {
+    pressed_at: Option<Point>,
+    is_dragging: bool,
+}
+
+impl State {
+    pub fn new() -> Self {
+        State::default()
+    }
+}
+
 pub struct SnapshotView<'a> {
     pattern: GridPattern,
     selection_state: SelectionState,
@@ -66,6 +82,44 @@ impl<'a> SnapshotView<'a> {
         self.selection_state = state;
         self
     }
+
+    /// Lets the whole pattern be dropped on a [`Grid`], see [`DragMessage`].
+    ///
+    /// `Picked` is only sent once the cursor moved a few pixels away from the
+    /// press, so clicks stay clicks. The snapshot is wrapped in a
+    /// [`DraggableSnapshotView`], a plain `SnapshotView` works with any message.
+    ///
+    /// [`Grid`]: crate::native::grid::Grid
+    pub fn on_drag<Message, F>(
+        self,
+        state: &'a mut State,
+        f: F,
+    ) -> DraggableSnapshotView<'a, Message>
+    where
+        F: 'a + Fn(DragMessage) -> Message,
+    {
+        DraggableSnapshotView {
+            snapshot: self,
+            state,
+            on_drag: Box::new(f),
+        }
+    }
+}
+
+/// A [`SnapshotView`] whose pattern can be dragged, see [`SnapshotView::on_drag`].
+pub struct DraggableSnapshotView<'a, Message> {
+    snapshot: SnapshotView<'a>,
+    state: &'a mut State,
+    on_drag: Box<dyn Fn(DragMessage) -> Message + 'a>,
+}
+
+// the (step, track) under the cursor, laid out like the events in draw
+fn get_hovered_step(bounds: Rectangle, cursor: Point) -> (usize, usize) {
+    let step_dim: Size = get_step_dimension(bounds, NUM_STEPS + 2, NUM_PERCS);
+    let step = ((cursor.x - bounds.x) / step_dim.width - 1.).max(0.) as usize;
+    let track = ((cursor.y - bounds.y) / step_dim.height).max(0.) as usize;
+
+    (step.min(NUM_STEPS - 1), track.min(NUM_PERCS - 1))
 }
 
 
@@ -232,3 +286,112 @@ where
         Element::new(snapshot)
     }
 }
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for DraggableSnapshotView<'a, Message>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        Widget::<Message, Renderer>::width(&self.snapshot)
+    }
+
+    fn height(&self) -> Length {
+        Widget::<Message, Renderer>::height(&self.snapshot)
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        Widget::<Message, Renderer>::layout(&self.snapshot, renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        Widget::<Message, Renderer>::draw(
+            &self.snapshot,
+            renderer,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        )
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+
+        let state = &mut *self.state;
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if bounds.contains(cursor_position) {
+                    state.pressed_at = Some(cursor_position);
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                if let (Some(origin), false) = (state.pressed_at, state.is_dragging) {
+                    if origin.distance(cursor_position) > DRAG_THRESHOLD {
+                        state.is_dragging = true;
+
+                        // the pattern keeps its shape around the grabbed step
+                        let anchor = get_hovered_step(bounds, origin);
+                        let payload = self.snapshot.pattern.get_drag_payload(anchor, false);
+
+                        shell.publish((self.on_drag)(DragMessage::Picked(payload)));
+                    }
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                // the drag source ends the drag, wherever it was dropped
+                if state.is_dragging {
+                    shell.publish((self.on_drag)(DragMessage::Canceled()));
+                }
+
+                *state = State::default();
+            }
+            _ => {}
+        }
+
+        // left to the parent, which may be reordering the snapshots
+        event::Status::Ignored
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        Widget::<Message, Renderer>::mouse_interaction(
+            &self.snapshot,
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+}
+
+impl<'a, Message, Renderer> From<DraggableSnapshotView<'a, Message>>
+    for Element<'a, Message, Renderer>
+where
+    Renderer: 'a + iced_native::Renderer,
+    Message: 'a,
+{
+    fn from(snapshot: DraggableSnapshotView<'a, Message>) -> Element<'a, Message, Renderer> {
+        Element::new(snapshot)
+    }
+}
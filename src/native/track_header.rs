@@ -0,0 +1,388 @@
+use iced_native::{
+    alignment, event, layout, mouse, renderer, text, Background, Clipboard, Color, Element,
+    Event, Layout, Length, Padding, Point, Rectangle, Shell, Size, Widget,
+};
+
+use crate::core::grid::{get_track_height, TRACK_MARGIN_BOTTOM};
+pub use crate::style::track_header::{Style, StyleSheet, Toggle};
+use crate::style::grid::GridColor;
+use ganic_no_std::NUM_PERCS;
+
+const TOGGLE_SIZE: f32 = 18.;
+const TOGGLE_SPACING: f32 = 4.;
+const NAME_PADDING: f32 = 8.;
+
+/// Messages of a [`TrackHeader`], tracks are indexed like the pattern
+/// (and [`State::set_mute`]), not like the grid rows.
+///
+/// `Select` follows `Focus` when [`TrackHeader::select_on_click`] is set and
+/// is applied to the grid with `GridMessage::SelectTrack(NUM_PERCS - 1 - track)`.
+///
+/// [`State::set_mute`]: crate::native::grid::State::set_mute
+#[derive(Debug, Clone, Copy)]
+pub enum TrackHeaderMessage {
+    Focus(usize),
+    Select(usize),
+    Mute(usize, bool),
+    Solo(usize, bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TrackToggle {
+    Mute,
+    Solo,
+}
+
+/// One row per track, laid out like the rows of a [`Grid`] of the same height.
+///
+/// [`Grid`]: crate::native::grid::Grid
+#[allow(missing_debug_implementations)]
+pub struct TrackHeader<'a, Message> {
+    names: Vec<String>,
+    mutes: [bool; NUM_PERCS],
+    solos: [bool; NUM_PERCS],
+    focused_track: Option<usize>,
+    select_on_click: bool,
+    on_event: Box<dyn Fn(TrackHeaderMessage) -> Message>,
+    width: Length,
+    height: Length,
+    padding: Padding,
+    style_sheet: Box<dyn StyleSheet + 'a>,
+}
+
+impl<'a, Message> TrackHeader<'a, Message> {
+    // names are indexed like the pattern, missing ones are left empty
+    pub fn new<F>(names: Vec<String>, on_event: F) -> Self
+    where
+        F: 'static + Fn(TrackHeaderMessage) -> Message,
+    {
+        TrackHeader {
+            names,
+            mutes: [false; NUM_PERCS],
+            solos: [false; NUM_PERCS],
+            focused_track: None,
+            select_on_click: false,
+            on_event: Box::new(on_event),
+            width: Length::Fill,
+            height: Length::Fill,
+            padding: Padding::ZERO,
+            style_sheet: Default::default(),
+        }
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    // use the grid padding to keep the rows aligned
+    pub fn padding<P: Into<Padding>>(mut self, padding: P) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    pub fn mutes(mut self, mutes: [bool; NUM_PERCS]) -> Self {
+        self.mutes = mutes;
+        self
+    }
+
+    pub fn solos(mut self, solos: [bool; NUM_PERCS]) -> Self {
+        self.solos = solos;
+        self
+    }
+
+    pub fn focused_track(mut self, track: usize) -> Self {
+        self.focused_track = Some(track);
+        self
+    }
+
+    // clicking a track name selects its events in the grid too
+    pub fn select_on_click(mut self, select_on_click: bool) -> Self {
+        self.select_on_click = select_on_click;
+        self
+    }
+
+    pub fn style(mut self, style: impl Into<Box<dyn StyleSheet + 'a>>) -> Self {
+        self.style_sheet = style.into();
+        self
+    }
+}
+
+// grid rows are upside down compared to the pattern
+fn get_track(row: usize) -> usize {
+    NUM_PERCS - 1 - row
+}
+
+fn get_row_bounds(bounds: Rectangle, row: usize) -> Rectangle {
+    let track_height = get_track_height(bounds.size());
+
+    Rectangle {
+        x: bounds.x,
+        y: bounds.y + row as f32 * track_height,
+        width: bounds.width,
+        height: track_height - TRACK_MARGIN_BOTTOM,
+    }
+}
+
+fn get_hovered_row(bounds: Rectangle, cursor: Point) -> Option<usize> {
+    (0..NUM_PERCS).find(|row| get_row_bounds(bounds, *row).contains(cursor))
+}
+
+fn get_toggle_bounds(row_bounds: Rectangle, toggle: TrackToggle) -> Rectangle {
+    let size = TOGGLE_SIZE.min(row_bounds.height);
+    let index = match toggle {
+        TrackToggle::Mute => 2.,
+        TrackToggle::Solo => 1.,
+    };
+
+    Rectangle {
+        x: row_bounds.x + row_bounds.width - index * (size + TOGGLE_SPACING),
+        y: row_bounds.center_y() - size / 2.,
+        width: size,
+        height: size,
+    }
+}
+
+fn get_hovered_toggle(row_bounds: Rectangle, cursor: Point) -> Option<TrackToggle> {
+    [TrackToggle::Mute, TrackToggle::Solo]
+        .into_iter()
+        .find(|toggle| get_toggle_bounds(row_bounds, *toggle).contains(cursor))
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for TrackHeader<'a, Message>
+where
+    Renderer: text::Renderer,
+{
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits
+            .width(self.width)
+            .height(self.height)
+            .pad(self.padding);
+
+        let mut content = layout::Node::new(limits.resolve(Size::ZERO));
+        content.move_to(Point::new(
+            self.padding.left.into(),
+            self.padding.top.into(),
+        ));
+
+        let size = limits.resolve(content.size()).pad(self.padding);
+
+        layout::Node::with_children(size, vec![content])
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let bounds = layout.children().next().unwrap().bounds();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if let Some(row) = get_hovered_row(bounds, cursor_position) {
+                    let track = get_track(row);
+                    let row_bounds = get_row_bounds(bounds, row);
+
+                    let messages = match get_hovered_toggle(row_bounds, cursor_position) {
+                        Some(TrackToggle::Mute) => {
+                            vec![TrackHeaderMessage::Mute(track, !self.mutes[track])]
+                        }
+                        Some(TrackToggle::Solo) => {
+                            vec![TrackHeaderMessage::Solo(track, !self.solos[track])]
+                        }
+                        None if self.select_on_click => vec![
+                            TrackHeaderMessage::Focus(track),
+                            TrackHeaderMessage::Select(track),
+                        ],
+                        None => vec![TrackHeaderMessage::Focus(track)],
+                    };
+
+                    messages.into_iter().for_each(|message| {
+                        shell.publish((self.on_event)(message));
+                    });
+
+                    return event::Status::Captured;
+                }
+            }
+            _ => {}
+        }
+
+        event::Status::Ignored
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.children().next().unwrap().bounds();
+        let style = self.style_sheet.default();
+        let hovered_toggle_style = self.style_sheet.hovered_toggle();
+
+        if let Some(background) = style.background {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: layout.bounds(),
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                background,
+            );
+        }
+
+        for row in 0..NUM_PERCS {
+            let track = get_track(row);
+            let row_bounds = get_row_bounds(bounds, row);
+
+            let row_bg_color = match self.focused_track {
+                Some(focused_track) if focused_track == track => style.focused_row_bg_color,
+                _ => style.row_bg_color,
+            };
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: row_bounds,
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                Background::Color(row_bg_color),
+            );
+
+            // color chip
+            let chip_color = match style.chip_color {
+                GridColor::Simple(color) => color,
+                GridColor::Multitrack(color_array) => color_array[row],
+            };
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        width: style.chip_width,
+                        ..row_bounds
+                    },
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                Background::Color(chip_color),
+            );
+
+            // name
+            if let Some(name) = self.names.get(track) {
+                let mute_bounds = get_toggle_bounds(row_bounds, TrackToggle::Mute);
+                let name_x = row_bounds.x + style.chip_width + NAME_PADDING;
+
+                renderer.fill_text(text::Text {
+                    content: name,
+                    bounds: Rectangle {
+                        x: name_x,
+                        y: row_bounds.center_y(),
+                        width: (mute_bounds.x - name_x - NAME_PADDING).max(0.),
+                        height: row_bounds.height,
+                    },
+                    size: style.name_size,
+                    color: style.name_color,
+                    font: Default::default(),
+                    horizontal_alignment: alignment::Horizontal::Left,
+                    vertical_alignment: alignment::Vertical::Center,
+                });
+            }
+
+            // mute & solo toggles
+            for (toggle, label, is_active) in [
+                (TrackToggle::Mute, "M", self.mutes[track]),
+                (TrackToggle::Solo, "S", self.solos[track]),
+            ] {
+                let toggle_bounds = get_toggle_bounds(row_bounds, toggle);
+                let toggle_style = match toggle_bounds.contains(cursor_position) {
+                    true => hovered_toggle_style,
+                    false => style.toggle,
+                };
+
+                let (bg_color, text_color) = match (is_active, toggle) {
+                    (true, TrackToggle::Mute) => {
+                        (toggle_style.mute_active_bg_color, toggle_style.active_text_color)
+                    }
+                    (true, TrackToggle::Solo) => {
+                        (toggle_style.solo_active_bg_color, toggle_style.active_text_color)
+                    }
+                    (false, _) => (toggle_style.bg_color, toggle_style.text_color),
+                };
+
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: toggle_bounds,
+                        border_radius: toggle_style.border_radius,
+                        border_width: 0.0,
+                        border_color: Color::TRANSPARENT,
+                    },
+                    Background::Color(bg_color),
+                );
+
+                renderer.fill_text(text::Text {
+                    content: label,
+                    bounds: Rectangle {
+                        x: toggle_bounds.center_x(),
+                        y: toggle_bounds.center_y(),
+                        ..toggle_bounds
+                    },
+                    size: toggle_style.text_size,
+                    color: text_color,
+                    font: Default::default(),
+                    horizontal_alignment: alignment::Horizontal::Center,
+                    vertical_alignment: alignment::Vertical::Center,
+                });
+            }
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let bounds = layout.children().next().unwrap().bounds();
+
+        match get_hovered_row(bounds, cursor_position) {
+            Some(row) => match get_hovered_toggle(get_row_bounds(bounds, row), cursor_position) {
+                Some(_) => mouse::Interaction::Pointer,
+                None => mouse::Interaction::default(),
+            },
+            None => mouse::Interaction::default(),
+        }
+    }
+}
+
+impl<'a, Message, Renderer> From<TrackHeader<'a, Message>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'a + text::Renderer,
+{
+    fn from(track_header: TrackHeader<'a, Message>) -> Element<'a, Message, Renderer> {
+        Element::new(track_header)
+    }
+}
@@ -15,6 +15,18 @@ pub fn darken(color: Color, amount: f32) -> Color {
     Color::from_rgb(srgb_color.red, srgb_color.green, srgb_color.blue)
 }
 
+// linear blend from a (ratio 0) to b (ratio 1)
+pub fn mix(a: Color, b: Color, ratio: f32) -> Color {
+    let ratio = ratio.max(0.).min(1.);
+
+    Color {
+        r: a.r + (b.r - a.r) * ratio,
+        g: a.g + (b.g - a.g) * ratio,
+        b: a.b + (b.b - a.b) * ratio,
+        a: a.a + (b.a - a.a) * ratio,
+    }
+}
+
 pub fn from_hex_unsafe(hex: &str) -> (f32, f32, f32) {
     let rgb: Rgb = Rgb::from_hex_str(hex).unwrap();
     rgb.as_tuple()
@@ -35,3 +47,189 @@ pub fn hex(hex_str: &str) -> Color {
         color_tuple.2 / 255.,
     )
 }
+
+// WCAG contrast ratios
+pub const CONTRAST_AA: f32 = 4.5;
+pub const CONTRAST_AA_LARGE: f32 = 3.;
+pub const CONTRAST_AAA: f32 = 7.;
+
+fn to_linear(channel: f32) -> f32 {
+    match channel <= 0.04045 {
+        true => channel / 12.92,
+        false => ((channel + 0.055) / 1.055).powf(2.4),
+    }
+}
+
+fn from_linear(channel: f32) -> f32 {
+    match channel <= 0.0031308 {
+        true => channel * 12.92,
+        false => 1.055 * channel.powf(1. / 2.4) - 0.055,
+    }
+}
+
+// (lightness, a, b), see https://bottosson.github.io/posts/oklab/
+fn to_oklab(color: Color) -> (f32, f32, f32) {
+    let (r, g, b) = (to_linear(color.r), to_linear(color.g), to_linear(color.b));
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    (
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    )
+}
+
+// linear rgb, out of [0, 1] when outside of the sRGB gamut
+fn from_oklab(lightness: f32, a: f32, b: f32) -> (f32, f32, f32) {
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    (
+        4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+        -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+        -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+    )
+}
+
+/// (lightness, chroma, hue in radians)
+pub fn to_oklch(color: Color) -> (f32, f32, f32) {
+    let (lightness, a, b) = to_oklab(color);
+
+    (lightness, a.hypot(b), b.atan2(a))
+}
+
+/// Converts back to sRGB, lowering the chroma until the color fits the gamut.
+pub fn from_oklch(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Color {
+    let in_gamut = |(r, g, b): (f32, f32, f32)| {
+        [r, g, b].iter().all(|channel| (-0.0001..=1.0001).contains(channel))
+    };
+    let to_rgb = |chroma: f32| from_oklab(lightness, chroma * hue.cos(), chroma * hue.sin());
+
+    let mut rgb = to_rgb(chroma);
+    if !in_gamut(rgb) {
+        let (mut low, mut high) = (0., chroma);
+
+        for _ in 0..16 {
+            let middle = (low + high) / 2.;
+            match in_gamut(to_rgb(middle)) {
+                true => low = middle,
+                false => high = middle,
+            }
+        }
+
+        rgb = to_rgb(low);
+    }
+
+    let channel = |channel: f32| from_linear(channel.max(0.).min(1.));
+    Color::from_rgba(channel(rgb.0), channel(rgb.1), channel(rgb.2), alpha)
+}
+
+/// Perceptual blend from a (ratio 0) to b (ratio 1) in OKLCH, along the
+/// shortest way around the hue circle.
+pub fn mix_oklch(a: Color, b: Color, ratio: f32) -> Color {
+    let ratio = ratio.max(0.).min(1.);
+    let (a_lightness, a_chroma, mut a_hue) = to_oklch(a);
+    let (b_lightness, b_chroma, mut b_hue) = to_oklch(b);
+
+    // greys have no hue, they take the one of the other color
+    if a_chroma < 0.0001 {
+        a_hue = b_hue;
+    }
+    if b_chroma < 0.0001 {
+        b_hue = a_hue;
+    }
+
+    let mut hue_delta = b_hue - a_hue;
+    if hue_delta > std::f32::consts::PI {
+        hue_delta -= 2. * std::f32::consts::PI;
+    } else if hue_delta < -std::f32::consts::PI {
+        hue_delta += 2. * std::f32::consts::PI;
+    }
+
+    from_oklch(
+        a_lightness + (b_lightness - a_lightness) * ratio,
+        a_chroma + (b_chroma - a_chroma) * ratio,
+        a_hue + hue_delta * ratio,
+        a.a + (b.a - a.a) * ratio,
+    )
+}
+
+/// `count` colors evenly spread through the stops, the first and last colors
+/// being the first and last stops.
+pub fn gradient(stops: &[Color], count: usize) -> Vec<Color> {
+    match (stops.len(), count) {
+        (0, _) | (_, 0) => vec![],
+        (1, _) => vec![stops[0]; count],
+        (_, 1) => vec![stops[0]],
+        _ => (0..count)
+            .map(|index| {
+                let position = index as f32 / (count - 1) as f32 * (stops.len() - 1) as f32;
+                let stop = (position.floor() as usize).min(stops.len() - 2);
+
+                mix_oklch(stops[stop], stops[stop + 1], position - stop as f32)
+            })
+            .collect(),
+    }
+}
+
+pub fn relative_luminance(color: Color) -> f32 {
+    0.2126 * to_linear(color.r) + 0.7152 * to_linear(color.g) + 0.0722 * to_linear(color.b)
+}
+
+/// WCAG contrast ratio, from 1 (same luminance) to 21 (black on white).
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Colorblind-safe palettes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    OkabeIto, // categorical, black left out for dark backgrounds
+    Viridis,
+    Cividis,
+}
+
+impl Preset {
+    pub fn stops(&self) -> Vec<Color> {
+        let stops: &[&str] = match self {
+            Preset::OkabeIto => &[
+                "e69f00", "56b4e9", "009e73", "f0e442", "0072b2", "d55e00", "cc79a7",
+            ],
+            Preset::Viridis => &["440154", "3b528b", "21918c", "5ec962", "fde725"],
+            Preset::Cividis => &["00204c", "414d6b", "7c7b78", "bcaf6f", "ffea46"],
+        };
+
+        stops.iter().map(|stop| hex(stop)).collect()
+    }
+
+    /// `count` colors of the palette. Categorical palettes are never blended,
+    /// their colors are repeated darker when they have fewer than `count`.
+    pub fn colors(&self, count: usize) -> Vec<Color> {
+        let stops = self.stops();
+
+        match self {
+            Preset::OkabeIto => stops
+                .iter()
+                .cycle()
+                .take(count)
+                .enumerate()
+                .map(|(index, stop)| match index / stops.len() {
+                    0 => *stop,
+                    repeat => {
+                        let (lightness, chroma, hue) = to_oklch(*stop);
+                        let lightness = (lightness - 0.15 * repeat as f32).max(0.2);
+
+                        from_oklch(lightness, chroma, hue, stop.a)
+                    }
+                })
+                .collect(),
+            _ => gradient(&stops, count),
+        }
+    }
+}
@@ -1,8 +1,9 @@
+use std::time::Duration;
 use ganic_no_std::NUM_PERCS;
 use iced_native::Color;
-use super::color_utils::{hex, lighten, darken};
+use super::color_utils::{gradient, hex, lighten, darken, Preset};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct WidgetBackground {
     pub bg_color: Color,
     pub border_width: f32,
@@ -10,30 +11,60 @@ pub struct WidgetBackground {
     pub border_color: Color
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GridColor {
     Simple(Color),
     Multitrack([Color; NUM_PERCS])
 }
 
-#[derive(Debug, Clone, Copy)]
+impl GridColor {
+    /// One color per track, blended through the stops in OKLCH.
+    pub fn gradient(stops: &[Color]) -> Self {
+        GridColor::from_track_colors(gradient(stops, NUM_PERCS))
+    }
+
+    pub fn preset(preset: Preset) -> Self {
+        GridColor::from_track_colors(preset.colors(NUM_PERCS))
+    }
+
+    fn from_track_colors(track_colors: Vec<Color>) -> Self {
+        let mut colors = [Color::BLACK; NUM_PERCS];
+        colors
+            .iter_mut()
+            .zip(track_colors)
+            .for_each(|(color, track_color)| *color = track_color);
+
+        GridColor::Multitrack(colors)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Stroke {
     pub color: Color,
     pub line_width: f32
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Style {
     pub event: Event,
     pub grid: Grid,
     pub background: Option<WidgetBackground>,
 
     pub selection_stroke: Stroke,
+    pub deselection_stroke: Stroke, // Alt-drag rectangle removing events from the selection
+    pub deselection_bg_color: Color,
+    pub ghost_stroke: Option<Stroke>, // original positions of a moved selection
+    pub focus_ring: Option<Stroke>, // drawn around the widget while it has keyboard focus
     pub selected_track_bg_color: Color,
-    pub current_step_bg_color: Color
+    pub current_step_bg_color: Color,
+    pub highlight_current_step: bool, // fill the cells being played
+    pub playhead: Option<Stroke>, // line at the fractional playhead position
+    pub context_menu: ContextMenu,
+    pub readout: Readout,
+    pub tooltip: Tooltip
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Grid {
     // BACKGROUNDS
     pub even_beat_bg_color: Color,
@@ -44,6 +75,7 @@ pub struct Grid {
     pub even_beat_line: Stroke,
     pub odd_beat_line: Stroke,
     pub edge_step_line: Stroke,
+    pub sub_step_line: Option<Stroke>, // snap positions inside a step
     pub track_margin_color: Color
 }
 
@@ -65,12 +97,13 @@ impl std::default::Default for Grid {
             even_beat_line: Stroke { color: hex("1A2122"), line_width: 1.0 },
             odd_beat_line: Stroke { color: hex("1A2122"), line_width: 1.0 },
             edge_step_line: Stroke { color: darken(hex("2a3637"), 0.14), line_width: 1. },
+            sub_step_line: Some(Stroke { color: darken(hex("2a3637"), 0.06), line_width: 1. }),
             track_margin_color: hex("1a2122")
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Event {
     // BACKGROUNDS
     pub contour_bg_color: Color,
@@ -81,6 +114,26 @@ pub struct Event {
     pub slider_highlighted_bg_color: GridColor,
     pub negative_offset_marker_bg_color: Color,
     pub positive_offset_marker_bg_color: Color,
+    pub velocity_display: VelocityDisplay,
+    pub flash: Option<Flash>, // played events, drawn over everything but the playhead
+}
+
+/// How the velocity of an event is shown, muted events keep their transparency.
+///
+/// Played events use `slider_highlighted_bg_color` in every mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VelocityDisplay {
+    /// A slider filled from the bottom of the event.
+    Bar,
+    /// The slider color over the whole event, as opaque as the velocity.
+    Opacity,
+    /// The whole event, from the lowest to the highest velocity color.
+    /// Played events are filled with the highlighted slider color.
+    Gradient(Color, Color),
+    /// The event shrinks towards its bottom, played events fill their step.
+    Height,
+    /// The velocity from 0 to 127, over the highlighted slider color while played.
+    Label { color: Color, size: f32 },
 }
 
 
@@ -127,15 +180,127 @@ impl std::default::Default for Event {
                 hex("ffffff")
             ]),
             negative_offset_marker_bg_color: hex("fc4860"),
-            positive_offset_marker_bg_color: hex("48bafc")
+            positive_offset_marker_bg_color: hex("48bafc"),
+            velocity_display: VelocityDisplay::Bar,
+            flash: Some(Flash {
+                color: Color { a: 0.6, ..hex("fafafa") },
+                duration: Duration::from_millis(300),
+                spread: 3.
+            })
+        }
+    }
+}
+
+/// A played event flashes, as bright as its velocity, then fades out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flash {
+    pub color: Color,
+    pub duration: Duration, // until the flash has faded out
+    pub spread: f32 // how much the flash overflows the event when it starts
+}
+
+impl Flash {
+    /// From the velocity when the event is hit down to 0 after `duration`.
+    pub fn intensity(&self, elapsed: Duration, velocity: f32) -> f32 {
+        if elapsed >= self.duration {
+            return 0.;
+        }
+
+        let remaining = 1. - elapsed.as_secs_f32() / self.duration.as_secs_f32();
+
+        velocity * remaining * remaining
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContextMenu {
+    pub background: WidgetBackground,
+    pub text_color: Color,
+    pub text_size: f32,
+    pub hovered_bg_color: Color,
+    pub hovered_text_color: Color
+}
+
+impl std::default::Default for ContextMenu {
+    fn default() -> Self {
+        ContextMenu {
+            background: WidgetBackground {
+                bg_color: hex("1f2829"),
+                border_width: 1.,
+                border_radius: 2.,
+                border_color: hex("354345")
+            },
+            text_color: hex("8ea5a8"),
+            text_size: 14.,
+            hovered_bg_color: hex("303d3e"),
+            hovered_text_color: hex("fafafa")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Readout {
+    pub background: WidgetBackground,
+    pub text_color: Color,
+    pub text_size: f32
+}
+
+impl std::default::Default for Readout {
+    fn default() -> Self {
+        Readout {
+            background: WidgetBackground {
+                bg_color: hex("1f2829"),
+                border_width: 1.,
+                border_radius: 2.,
+                border_color: hex("354345")
+            },
+            text_color: hex("fafafa"),
+            text_size: 12.
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tooltip {
+    pub background: WidgetBackground,
+    pub text_color: Color,
+    pub text_size: f32
+}
+
+impl std::default::Default for Tooltip {
+    fn default() -> Self {
+        Tooltip {
+            background: WidgetBackground {
+                bg_color: hex("1f2829"),
+                border_width: 1.,
+                border_radius: 2.,
+                border_color: hex("354345")
+            },
+            text_color: hex("8ea5a8"),
+            text_size: 13.
+        }
+    }
+}
 
+/// Only the default style is required, the others fall back to it.
 pub trait StyleSheet {
     fn default(&self) -> Style;
-    fn dragging_selection(&self) -> Style;
+
+    fn hovered(&self) -> Style {
+        self.default()
+    }
+
+    fn focused(&self) -> Style {
+        self.default()
+    }
+
+    fn dragging_selection(&self) -> Style {
+        self.default()
+    }
+
+    fn disabled(&self) -> Style {
+        self.default()
+    }
 }
 
 pub struct MyDefault;
@@ -148,17 +313,54 @@ impl StyleSheet for MyDefault {
             background: None,
 
             selection_stroke: Stroke { color: hex("8ea5a8"), line_width: 1.0 },
-            selected_track_bg_color: lighten(Color::BLACK, 0.7),
-            current_step_bg_color: hex("303d3e")
+            deselection_stroke: Stroke { color: hex("fc4860"), line_width: 1.0 },
+            deselection_bg_color: Color { a: 0.15, ..hex("fc4860") },
+            ghost_stroke: Some(Stroke { color: Color { a: 0.35, ..hex("fafafa") }, line_width: 1.0 }),
+            focus_ring: Some(Stroke { color: hex("354345"), line_width: 1.0 }),
+            selected_track_bg_color: Color { a: 0.5, ..hex("354345") },
+            current_step_bg_color: hex("303d3e"),
+            highlight_current_step: true,
+            playhead: Some(Stroke { color: hex("fafafa"), line_width: 2.0 }),
+            context_menu: ContextMenu::default(),
+            readout: Readout::default(),
+            tooltip: Tooltip::default()
+        }
+    }
+
+    fn hovered(&self) -> Style {
+        Style {
+            grid: Grid {
+                edge_step_bg_color: hex("252f30"),
+                ..Grid::default()
+            },
+            ..self.default()
         }
     }
 
+    fn focused(&self) -> Style {
+        self.hovered()
+    }
+
     fn dragging_selection(&self) -> Style {
         Style {
             event: Event {
                 stroke: Stroke { color: lighten(Color::BLACK, 0.1), line_width: 1. },
                 ..Event::default()
             },
+            ..self.focused()
+        }
+    }
+
+    fn disabled(&self) -> Style {
+        Style {
+            event: Event {
+                bg_color: GridColor::Simple(hex("2a3637")),
+                slider_bg_color: GridColor::Simple(hex("354345")),
+                slider_highlighted_bg_color: GridColor::Simple(hex("354345")),
+                ..Event::default()
+            },
+            focus_ring: None,
+            selected_track_bg_color: Color::TRANSPARENT,
             ..self.default()
         }
     }
@@ -13,7 +13,7 @@ pub trait StyleSheet {
     fn default(&self) -> Style;
 }
 
-struct Default;
+pub struct Default;
 
 impl StyleSheet for Default {
     fn default(&self) -> Style {
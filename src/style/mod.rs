@@ -2,4 +2,8 @@ pub mod color_utils;
 pub mod grid;
 pub mod h_list;
 pub mod multi_slider;
+pub mod ruler;
 pub mod snapshot;
+#[cfg(feature = "theme")]
+pub mod theme;
+pub mod track_header;
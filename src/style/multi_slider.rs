@@ -24,7 +24,7 @@ pub trait StyleSheet {
     fn hovered(&self, primary_color: Color) -> Slider;
 }
 
-struct Default;
+pub struct Default;
 
 impl StyleSheet for Default {
     fn default(&self, primary_color: Color) -> Style {
@@ -0,0 +1,69 @@
+use super::color_utils::{hex, lighten};
+use iced_native::{Background, Color};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    pub background: Option<Background>,
+    // same beat colors as the grid so the columns read as one
+    pub even_beat_bg_color: Color,
+    pub odd_beat_bg_color: Color,
+    pub edge_step_bg_color: Color,
+    pub tick_color: Color,
+    pub text_color: Color,
+    pub beat_text_color: Color,
+    pub text_size: f32,
+    pub playhead_color: Color,
+    pub playhead_width: f32,
+    pub loop_region_color: Color,
+    pub loop_handle_color: Color,
+    pub loop_handle_width: f32,
+}
+
+pub trait StyleSheet {
+    fn default(&self) -> Style;
+    fn hovered_handle(&self) -> Color;
+}
+
+pub struct Default;
+
+impl StyleSheet for Default {
+    fn default(&self) -> Style {
+        Style {
+            background: Some(Background::Color(hex("1a2122"))),
+            even_beat_bg_color: hex("2a3637"),
+            odd_beat_bg_color: hex("252f30"),
+            edge_step_bg_color: hex("1f2829"),
+            tick_color: hex("1a2122"),
+            text_color: hex("5f7173"),
+            beat_text_color: hex("8ea5a8"),
+            text_size: 12.,
+            playhead_color: hex("fafafa"),
+            playhead_width: 2.,
+            loop_region_color: Color {
+                a: 0.35,
+                ..hex("48bafc")
+            },
+            loop_handle_color: hex("48bafc"),
+            loop_handle_width: 4.,
+        }
+    }
+
+    fn hovered_handle(&self) -> Color {
+        lighten(hex("48bafc"), 0.2)
+    }
+}
+
+impl<'a> std::default::Default for Box<dyn StyleSheet + 'a> {
+    fn default() -> Self {
+        Box::new(Default)
+    }
+}
+
+impl<'a, T> From<T> for Box<dyn StyleSheet + 'a>
+where
+    T: StyleSheet + 'a,
+{
+    fn from(style: T) -> Self {
+        Box::new(style)
+    }
+}
@@ -0,0 +1,682 @@
+//! Styles loaded from TOML theme files.
+//!
+//! Every field of a theme is optional and overrides the default style of its
+//! widget, colors are written `"#rrggbb"` or `"#rrggbbaa"`:
+//!
+//! ```toml
+//! [grid]
+//! selected_track_bg_color = "#35434580"
+//! playhead = { color = "#fafafa", line_width = 2.0 }
+//!
+//! [grid.event]
+//! bg_color = "#ff7e53" # or one color per track
+//! velocity_display = { gradient = ["#354345", "#fafafa"] }
+//!
+//! # applied over [grid] while the grid is hovered
+//! [grid.hovered.grid]
+//! edge_step_bg_color = "#252f30"
+//!
+//! [multi_slider.slider]
+//! color = "#48bafc"
+//!
+//! [snapshot]
+//! selected_step_color = "#48bafc"
+//! ```
+//!
+//! Each section is a `StyleSheet` of its widget, e.g.
+//! `Grid::new(..).style(theme.grid)`.
+
+use std::{convert::TryFrom, fmt, fs, io, path::Path, time::Duration};
+
+use iced_native::{Background, Color};
+use serde::Deserialize;
+
+use ganic_no_std::NUM_PERCS;
+
+use super::{grid, h_list, multi_slider, snapshot};
+
+#[cfg(feature = "theme-watch")]
+mod watch;
+#[cfg(feature = "theme-watch")]
+pub use watch::ThemeWatcher;
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    #[cfg(feature = "theme-watch")]
+    Watch(notify::Error),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(error) => write!(f, "cannot read the theme: {}", error),
+            ThemeError::Parse(error) => write!(f, "invalid theme: {}", error),
+            #[cfg(feature = "theme-watch")]
+            ThemeError::Watch(error) => write!(f, "cannot watch the theme: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+impl From<io::Error> for ThemeError {
+    fn from(error: io::Error) -> Self {
+        ThemeError::Io(error)
+    }
+}
+
+impl From<toml::de::Error> for ThemeError {
+    fn from(error: toml::de::Error) -> Self {
+        ThemeError::Parse(error)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub grid: GridTheme,
+    pub multi_slider: MultiSliderTheme,
+    pub snapshot: SnapshotTheme,
+    pub h_list: HListTheme,
+}
+
+impl Theme {
+    pub fn from_toml(source: &str) -> Result<Self, ThemeError> {
+        Ok(toml::from_str(source)?)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ThemeError> {
+        Theme::from_toml(&fs::read_to_string(path)?)
+    }
+}
+
+/// A color written `"#rrggbb"` or `"#rrggbbaa"`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct HexColor(pub Color);
+
+impl TryFrom<String> for HexColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let digits = value.trim_start_matches('#');
+        let channel = |index: usize| {
+            digits
+                .get(index * 2..index * 2 + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+        };
+
+        match (digits.len(), channel(0), channel(1), channel(2)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(HexColor(Color::from_rgb8(r, g, b))),
+            (8, Some(r), Some(g), Some(b)) => match channel(3) {
+                Some(a) => Ok(HexColor(Color::from_rgba8(r, g, b, a as f32 / 255.))),
+                None => Err(format!("invalid color {:?}", value)),
+            },
+            _ => Err(format!("invalid color {:?}, expected #rrggbb or #rrggbbaa", value)),
+        }
+    }
+}
+
+impl From<HexColor> for Color {
+    fn from(color: HexColor) -> Self {
+        color.0
+    }
+}
+
+/// One color, or one per track.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "GridColorValue")]
+pub struct GridColorOverride(pub grid::GridColor);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GridColorValue {
+    Simple(HexColor),
+    Multitrack(Vec<HexColor>),
+}
+
+impl TryFrom<GridColorValue> for GridColorOverride {
+    type Error = String;
+
+    fn try_from(value: GridColorValue) -> Result<Self, Self::Error> {
+        match value {
+            GridColorValue::Simple(color) => Ok(GridColorOverride(grid::GridColor::Simple(color.0))),
+            GridColorValue::Multitrack(colors) if colors.len() == NUM_PERCS => {
+                let mut color_array = [Color::BLACK; NUM_PERCS];
+                color_array
+                    .iter_mut()
+                    .zip(colors)
+                    .for_each(|(color, value)| *color = value.0);
+
+                Ok(GridColorOverride(grid::GridColor::Multitrack(color_array)))
+            }
+            GridColorValue::Multitrack(colors) => Err(format!(
+                "expected {} track colors, got {}",
+                NUM_PERCS,
+                colors.len()
+            )),
+        }
+    }
+}
+
+impl From<GridColorOverride> for grid::GridColor {
+    fn from(color: GridColorOverride) -> Self {
+        color.0
+    }
+}
+
+// overrides the fields set in the theme
+macro_rules! apply {
+    ($style:expr, $theme:expr, $($field:ident),+) => {
+        $(
+            if let Some(value) = $theme.$field {
+                $style.$field = value.into();
+            }
+        )+
+    };
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrokeOverride {
+    pub color: Option<HexColor>,
+    pub line_width: Option<f32>,
+}
+
+impl StrokeOverride {
+    fn apply(&self, mut stroke: grid::Stroke) -> grid::Stroke {
+        apply!(stroke, self, color, line_width);
+        stroke
+    }
+
+    // strokes missing from the style are drawn once they're themed
+    fn apply_optional(&self, stroke: Option<grid::Stroke>) -> Option<grid::Stroke> {
+        Some(self.apply(stroke.unwrap_or(grid::Stroke {
+            color: Color::BLACK,
+            line_width: 1.,
+        })))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WidgetBackgroundOverride {
+    pub bg_color: Option<HexColor>,
+    pub border_width: Option<f32>,
+    pub border_radius: Option<f32>,
+    pub border_color: Option<HexColor>,
+}
+
+impl WidgetBackgroundOverride {
+    fn apply(&self, background: Option<grid::WidgetBackground>) -> grid::WidgetBackground {
+        let mut background = background.unwrap_or(grid::WidgetBackground {
+            bg_color: Color::TRANSPARENT,
+            border_width: 0.,
+            border_radius: 0.,
+            border_color: Color::TRANSPARENT,
+        });
+
+        apply!(background, self, bg_color, border_width, border_radius, border_color);
+        background
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventOverride {
+    pub contour_bg_color: Option<HexColor>,
+    pub contour_width: Option<f32>,
+    pub bg_color: Option<GridColorOverride>,
+    pub stroke: Option<StrokeOverride>,
+    pub slider_bg_color: Option<GridColorOverride>,
+    pub slider_highlighted_bg_color: Option<GridColorOverride>,
+    pub negative_offset_marker_bg_color: Option<HexColor>,
+    pub positive_offset_marker_bg_color: Option<HexColor>,
+    pub velocity_display: Option<VelocityDisplayOverride>,
+    pub flash: Option<FlashOverride>,
+}
+
+/// Written `"bar"`, `"opacity"`, `"height"`, `{ gradient = ["#rrggbb", "#rrggbb"] }`
+/// or `{ label = { color = "#rrggbb", size = 12.0 } }`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum VelocityDisplayOverride {
+    Bar,
+    Opacity,
+    Gradient(HexColor, HexColor),
+    Height,
+    Label { color: HexColor, size: f32 },
+}
+
+impl From<VelocityDisplayOverride> for grid::VelocityDisplay {
+    fn from(display: VelocityDisplayOverride) -> Self {
+        match display {
+            VelocityDisplayOverride::Bar => grid::VelocityDisplay::Bar,
+            VelocityDisplayOverride::Opacity => grid::VelocityDisplay::Opacity,
+            VelocityDisplayOverride::Gradient(low, high) => {
+                grid::VelocityDisplay::Gradient(low.0, high.0)
+            }
+            VelocityDisplayOverride::Height => grid::VelocityDisplay::Height,
+            VelocityDisplayOverride::Label { color, size } => grid::VelocityDisplay::Label {
+                color: color.0,
+                size,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FlashOverride {
+    pub enabled: Option<bool>, // `false` turns the flashes off
+    pub color: Option<HexColor>,
+    pub duration_ms: Option<u64>,
+    pub spread: Option<f32>,
+}
+
+impl FlashOverride {
+    fn apply(&self, flash: Option<grid::Flash>) -> Option<grid::Flash> {
+        if self.enabled == Some(false) {
+            return None;
+        }
+
+        let mut flash = flash.unwrap_or(grid::Flash {
+            color: Color::WHITE,
+            duration: Duration::from_millis(300),
+            spread: 0.,
+        });
+
+        apply!(flash, self, color, spread);
+        if let Some(duration_ms) = self.duration_ms {
+            flash.duration = Duration::from_millis(duration_ms);
+        }
+
+        Some(flash)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContextMenuOverride {
+    pub background: Option<WidgetBackgroundOverride>,
+    pub text_color: Option<HexColor>,
+    pub text_size: Option<f32>,
+    pub hovered_bg_color: Option<HexColor>,
+    pub hovered_text_color: Option<HexColor>,
+}
+
+impl ContextMenuOverride {
+    fn apply(&self, mut menu: grid::ContextMenu) -> grid::ContextMenu {
+        if let Some(background) = self.background {
+            menu.background = background.apply(Some(menu.background));
+        }
+
+        apply!(menu, self, text_color, text_size, hovered_bg_color, hovered_text_color);
+        menu
+    }
+}
+
+/// The velocity readout or the tooltip.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LabelOverride {
+    pub background: Option<WidgetBackgroundOverride>,
+    pub text_color: Option<HexColor>,
+    pub text_size: Option<f32>,
+}
+
+impl LabelOverride {
+    fn apply_readout(&self, mut readout: grid::Readout) -> grid::Readout {
+        if let Some(background) = self.background {
+            readout.background = background.apply(Some(readout.background));
+        }
+
+        apply!(readout, self, text_color, text_size);
+        readout
+    }
+
+    fn apply_tooltip(&self, mut tooltip: grid::Tooltip) -> grid::Tooltip {
+        if let Some(background) = self.background {
+            tooltip.background = background.apply(Some(tooltip.background));
+        }
+
+        apply!(tooltip, self, text_color, text_size);
+        tooltip
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GridLinesOverride {
+    pub even_beat_bg_color: Option<HexColor>,
+    pub odd_beat_bg_color: Option<HexColor>,
+    pub edge_step_bg_color: Option<HexColor>,
+    pub even_beat_line: Option<StrokeOverride>,
+    pub odd_beat_line: Option<StrokeOverride>,
+    pub edge_step_line: Option<StrokeOverride>,
+    pub sub_step_line: Option<StrokeOverride>,
+    pub track_margin_color: Option<HexColor>,
+}
+
+/// Overrides of every [`grid::StyleSheet`] style, and of each state in its
+/// own section, e.g. `[grid.hovered]`, applied over them.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GridTheme {
+    pub event: EventOverride,
+    pub grid: GridLinesOverride,
+    pub background: Option<WidgetBackgroundOverride>,
+    pub selection_stroke: Option<StrokeOverride>,
+    pub deselection_stroke: Option<StrokeOverride>,
+    pub deselection_bg_color: Option<HexColor>,
+    pub ghost_stroke: Option<StrokeOverride>,
+    pub focus_ring: Option<StrokeOverride>,
+    pub selected_track_bg_color: Option<HexColor>,
+    pub current_step_bg_color: Option<HexColor>,
+    pub highlight_current_step: Option<bool>,
+    pub playhead: Option<StrokeOverride>,
+    pub context_menu: Option<ContextMenuOverride>,
+    pub readout: Option<LabelOverride>,
+    pub tooltip: Option<LabelOverride>,
+
+    pub hovered: Option<GridStateTheme>,
+    pub focused: Option<GridStateTheme>,
+    pub dragging_selection: Option<GridStateTheme>,
+    pub disabled: Option<GridStateTheme>,
+}
+
+/// The overrides of a single grid state, which has no states of its own.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "GridTheme")]
+pub struct GridStateTheme(pub Box<GridTheme>);
+
+impl TryFrom<GridTheme> for GridStateTheme {
+    type Error = String;
+
+    fn try_from(theme: GridTheme) -> Result<Self, Self::Error> {
+        let has_states = theme.hovered.is_some()
+            || theme.focused.is_some()
+            || theme.dragging_selection.is_some()
+            || theme.disabled.is_some();
+
+        match has_states {
+            true => Err(String::from("grid states can't be nested")),
+            false => Ok(GridStateTheme(Box::new(theme))),
+        }
+    }
+}
+
+impl GridTheme {
+    pub fn apply(&self, mut style: grid::Style) -> grid::Style {
+        let event = &self.event;
+        apply!(
+            style.event,
+            event,
+            contour_bg_color,
+            contour_width,
+            bg_color,
+            slider_bg_color,
+            slider_highlighted_bg_color,
+            negative_offset_marker_bg_color,
+            positive_offset_marker_bg_color,
+            velocity_display
+        );
+        if let Some(stroke) = event.stroke {
+            style.event.stroke = stroke.apply(style.event.stroke);
+        }
+        if let Some(flash) = event.flash {
+            style.event.flash = flash.apply(style.event.flash);
+        }
+
+        let lines = &self.grid;
+        apply!(
+            style.grid,
+            lines,
+            even_beat_bg_color,
+            odd_beat_bg_color,
+            edge_step_bg_color,
+            track_margin_color
+        );
+        if let Some(stroke) = lines.even_beat_line {
+            style.grid.even_beat_line = stroke.apply(style.grid.even_beat_line);
+        }
+        if let Some(stroke) = lines.odd_beat_line {
+            style.grid.odd_beat_line = stroke.apply(style.grid.odd_beat_line);
+        }
+        if let Some(stroke) = lines.edge_step_line {
+            style.grid.edge_step_line = stroke.apply(style.grid.edge_step_line);
+        }
+        if let Some(stroke) = lines.sub_step_line {
+            style.grid.sub_step_line = stroke.apply_optional(style.grid.sub_step_line);
+        }
+
+        if let Some(background) = self.background {
+            style.background = Some(background.apply(style.background));
+        }
+        if let Some(stroke) = self.selection_stroke {
+            style.selection_stroke = stroke.apply(style.selection_stroke);
+        }
+        if let Some(stroke) = self.deselection_stroke {
+            style.deselection_stroke = stroke.apply(style.deselection_stroke);
+        }
+        if let Some(stroke) = self.ghost_stroke {
+            style.ghost_stroke = stroke.apply_optional(style.ghost_stroke);
+        }
+        if let Some(stroke) = self.focus_ring {
+            style.focus_ring = stroke.apply_optional(style.focus_ring);
+        }
+        if let Some(stroke) = self.playhead {
+            style.playhead = stroke.apply_optional(style.playhead);
+        }
+        if let Some(menu) = self.context_menu {
+            style.context_menu = menu.apply(style.context_menu);
+        }
+        if let Some(readout) = self.readout {
+            style.readout = readout.apply_readout(style.readout);
+        }
+        if let Some(tooltip) = self.tooltip {
+            style.tooltip = tooltip.apply_tooltip(style.tooltip);
+        }
+
+        apply!(
+            style,
+            self,
+            deselection_bg_color,
+            selected_track_bg_color,
+            current_step_bg_color,
+            highlight_current_step
+        );
+
+        style
+    }
+
+    fn apply_state(&self, style: grid::Style, state: &Option<GridStateTheme>) -> grid::Style {
+        let style = self.apply(style);
+
+        match state {
+            Some(state) => state.0.apply(style),
+            None => style,
+        }
+    }
+}
+
+impl grid::StyleSheet for GridTheme {
+    fn default(&self) -> grid::Style {
+        self.apply(grid::MyDefault.default())
+    }
+
+    fn hovered(&self) -> grid::Style {
+        self.apply_state(grid::MyDefault.hovered(), &self.hovered)
+    }
+
+    fn focused(&self) -> grid::Style {
+        self.apply_state(grid::MyDefault.focused(), &self.focused)
+    }
+
+    fn dragging_selection(&self) -> grid::Style {
+        self.apply_state(grid::MyDefault.dragging_selection(), &self.dragging_selection)
+    }
+
+    fn disabled(&self) -> grid::Style {
+        self.apply_state(grid::MyDefault.disabled(), &self.disabled)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SliderOverride {
+    pub color: Option<HexColor>,
+    pub marker_height: Option<f32>,
+    pub marker_color: Option<HexColor>,
+}
+
+impl SliderOverride {
+    fn apply(&self, mut slider: multi_slider::Slider) -> multi_slider::Slider {
+        apply!(slider, self, color, marker_height, marker_color);
+        slider
+    }
+}
+
+/// Overrides of every [`multi_slider::StyleSheet`] style. The sliders come
+/// from the primary color unless `[multi_slider.slider]` sets them, and each
+/// state section, e.g. `[multi_slider.hovered]`, is applied over it.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MultiSliderTheme {
+    pub background: Option<HexColor>,
+    pub border_radius: Option<f32>,
+    pub border_width: Option<f32>,
+    pub border_color: Option<HexColor>,
+    pub slider: SliderOverride,
+
+    pub active: Option<SliderOverride>,
+    pub highlight: Option<SliderOverride>,
+    pub hovered: Option<SliderOverride>,
+}
+
+impl MultiSliderTheme {
+    pub fn apply(
+        &self,
+        mut style: multi_slider::Style,
+        state: Option<SliderOverride>,
+    ) -> multi_slider::Style {
+        if let Some(background) = self.background {
+            style.background = Some(Background::Color(background.0));
+        }
+
+        apply!(style, self, border_radius, border_width, border_color);
+        style.slider = self.apply_slider(style.slider, state);
+        style
+    }
+
+    fn apply_slider(
+        &self,
+        slider: multi_slider::Slider,
+        state: Option<SliderOverride>,
+    ) -> multi_slider::Slider {
+        let slider = self.slider.apply(slider);
+
+        match state {
+            Some(state) => state.apply(slider),
+            None => slider,
+        }
+    }
+}
+
+impl multi_slider::StyleSheet for MultiSliderTheme {
+    fn default(&self, primary_color: Color) -> multi_slider::Style {
+        self.apply(multi_slider::Default.default(primary_color), None)
+    }
+
+    fn active(&self, primary_color: Color) -> multi_slider::Style {
+        self.apply(multi_slider::Default.active(primary_color), self.active)
+    }
+
+    fn highlight(&self, primary_color: Color) -> multi_slider::Slider {
+        self.apply_slider(multi_slider::Default.highlight(primary_color), self.highlight)
+    }
+
+    fn hovered(&self, primary_color: Color) -> multi_slider::Slider {
+        self.apply_slider(multi_slider::Default.hovered(primary_color), self.hovered)
+    }
+}
+
+/// Overrides of every [`snapshot::StyleSheet`] style, each with its step color.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SnapshotTheme {
+    pub background: Option<HexColor>,
+    pub border_radius: Option<f32>,
+    pub border_width: Option<f32>,
+    pub border_color: Option<HexColor>,
+    pub step_color: Option<HexColor>,
+    pub selected_step_color: Option<HexColor>,
+    pub dirty_step_color: Option<HexColor>,
+    pub line_edge_color: Option<HexColor>,
+    pub line_division_color: Option<HexColor>,
+}
+
+impl SnapshotTheme {
+    pub fn apply(&self, mut style: snapshot::Style, step_color: Option<HexColor>) -> snapshot::Style {
+        if let Some(background) = self.background {
+            style.background = Some(Background::Color(background.0));
+        }
+        if let Some(step_color) = step_color {
+            style.step_color = step_color.0;
+        }
+
+        apply!(
+            style,
+            self,
+            border_radius,
+            border_width,
+            border_color,
+            line_edge_color,
+            line_division_color
+        );
+        style
+    }
+}
+
+impl snapshot::StyleSheet for SnapshotTheme {
+    fn default(&self) -> snapshot::Style {
+        self.apply(snapshot::Default.default(), self.step_color)
+    }
+
+    fn selected(&self) -> snapshot::Style {
+        self.apply(snapshot::Default.selected(), self.selected_step_color)
+    }
+
+    fn dirty(&self) -> snapshot::Style {
+        self.apply(snapshot::Default.dirty(), self.dirty_step_color)
+    }
+}
+
+/// Overrides of the [`h_list::StyleSheet`] style.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HListTheme {
+    pub background: Option<HexColor>,
+    pub border_radius: Option<f32>,
+    pub border_width: Option<f32>,
+    pub border_color: Option<HexColor>,
+}
+
+impl HListTheme {
+    pub fn apply(&self, mut style: h_list::Style) -> h_list::Style {
+        if let Some(background) = self.background {
+            style.background = Some(Background::Color(background.0));
+        }
+
+        apply!(style, self, border_radius, border_width, border_color);
+        style
+    }
+}
+
+impl h_list::StyleSheet for HListTheme {
+    fn default(&self) -> h_list::Style {
+        self.apply(h_list::Default.default())
+    }
+}
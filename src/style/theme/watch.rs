@@ -0,0 +1,85 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::{Theme, ThemeError};
+
+impl From<notify::Error> for ThemeError {
+    fn from(error: notify::Error) -> Self {
+        ThemeError::Watch(error)
+    }
+}
+
+/// Reloads a theme file when it changes on disk.
+///
+/// Nothing runs on the UI thread: call [`ThemeWatcher::poll`] from a periodic
+/// message of the application (e.g. a `time::every` subscription) and restyle
+/// the widgets with the new theme.
+pub struct ThemeWatcher {
+    path: PathBuf,
+    receiver: Receiver<notify::Result<Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl ThemeWatcher {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, ThemeError> {
+        let path = path.as_ref().to_path_buf();
+        let (sender, receiver) = channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+
+        // editors often replace the file on save, so the directory is watched
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        watcher.watch(directory, RecursiveMode::NonRecursive)?;
+
+        Ok(ThemeWatcher {
+            path,
+            receiver,
+            _watcher: watcher,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the theme as it currently is on disk.
+    pub fn load(&self) -> Result<Theme, ThemeError> {
+        Theme::load(&self.path)
+    }
+
+    /// Returns the reloaded theme if the file changed since the last poll.
+    pub fn poll(&self) -> Option<Result<Theme, ThemeError>> {
+        let mut changed = false;
+
+        for event in self.receiver.try_iter() {
+            match event {
+                Ok(event) => changed |= self.is_change(&event),
+                Err(error) => return Some(Err(error.into())),
+            }
+        }
+
+        match changed {
+            true => Some(self.load()),
+            false => None,
+        }
+    }
+
+    fn is_change(&self, event: &Event) -> bool {
+        let is_write = matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_)
+        );
+
+        is_write
+            && event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == self.path.file_name())
+    }
+}
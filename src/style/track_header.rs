@@ -0,0 +1,93 @@
+use super::color_utils::{darken, hex, lighten};
+use super::grid::GridColor;
+use iced_native::{Background, Color};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    pub background: Option<Background>,
+    pub row_bg_color: Color,
+    pub focused_row_bg_color: Color,
+    // indexed like the grid rows, so it can share the grid event colors
+    pub chip_color: GridColor,
+    pub chip_width: f32,
+    pub name_color: Color,
+    pub name_size: f32,
+    pub toggle: Toggle,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Toggle {
+    pub bg_color: Color,
+    pub text_color: Color,
+    pub text_size: f32,
+    pub border_radius: f32,
+    pub mute_active_bg_color: Color,
+    pub solo_active_bg_color: Color,
+    pub active_text_color: Color,
+}
+
+pub trait StyleSheet {
+    fn default(&self) -> Style;
+    fn hovered_toggle(&self) -> Toggle;
+}
+
+pub struct Default;
+
+impl StyleSheet for Default {
+    fn default(&self) -> Style {
+        Style {
+            background: Some(Background::Color(hex("1a2122"))),
+            row_bg_color: hex("252f30"),
+            focused_row_bg_color: hex("303d3e"),
+            chip_color: GridColor::Multitrack([
+                darken(hex("ff7e53"), 0.12),
+                darken(hex("eb8c63"), 0.13),
+                darken(hex("d69a73"), 0.14),
+                darken(hex("c2a883"), 0.15),
+                darken(hex("aeb693"), 0.16),
+                darken(hex("99c4a4"), 0.17),
+                darken(hex("85d2b4"), 0.18),
+                darken(hex("71e0c4"), 0.19),
+                darken(hex("5ceed4"), 0.20),
+                darken(hex("48fce4"), 0.21),
+            ]),
+            chip_width: 4.,
+            name_color: hex("8ea5a8"),
+            name_size: 14.,
+            toggle: Toggle {
+                bg_color: hex("1f2829"),
+                text_color: hex("8ea5a8"),
+                text_size: 12.,
+                border_radius: 2.,
+                mute_active_bg_color: hex("fc4860"),
+                solo_active_bg_color: hex("48bafc"),
+                active_text_color: hex("fafafa"),
+            },
+        }
+    }
+
+    fn hovered_toggle(&self) -> Toggle {
+        let toggle = self.default().toggle;
+
+        Toggle {
+            bg_color: lighten(toggle.bg_color, 0.05),
+            text_color: hex("fafafa"),
+            ..toggle
+        }
+    }
+}
+
+impl<'a> std::default::Default for Box<dyn StyleSheet + 'a> {
+    fn default() -> Self {
+        Box::new(Default)
+    }
+}
+
+impl<'a, T> From<T> for Box<dyn StyleSheet + 'a>
+where
+    T: StyleSheet + 'a,
+{
+    fn from(style: T) -> Self {
+        Box::new(style)
+    }
+}
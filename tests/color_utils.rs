@@ -0,0 +1,117 @@
+//! Color conversions and palettes of `style::color_utils`.
+
+use iced_native::Color;
+
+use ganic_no_std::NUM_PERCS;
+use iced_sequencing::style::color_utils::{
+    contrast_ratio, from_oklch, gradient, hex, to_oklch, Preset,
+};
+use iced_sequencing::style::grid::GridColor;
+
+const EPSILON: f32 = 0.001;
+
+fn assert_close(a: Color, b: Color) {
+    let channels = [(a.r, b.r), (a.g, b.g), (a.b, b.b), (a.a, b.a)];
+
+    assert!(
+        channels.iter().all(|(a, b)| (a - b).abs() < EPSILON),
+        "{:?} != {:?}",
+        a,
+        b
+    );
+}
+
+#[test]
+fn contrast_of_black_on_white() {
+    assert!((contrast_ratio(Color::BLACK, Color::WHITE) - 21.).abs() < EPSILON);
+    assert!((contrast_ratio(Color::WHITE, Color::BLACK) - 21.).abs() < EPSILON);
+    assert!((contrast_ratio(hex("ff7e53"), hex("ff7e53")) - 1.).abs() < EPSILON);
+}
+
+#[test]
+fn oklch_round_trip() {
+    let colors = [
+        Color::BLACK,
+        Color::WHITE,
+        hex("ff7e53"),
+        hex("48fce4"),
+        hex("354345"),
+        Color::from_rgb(1., 0., 0.),
+        Color::from_rgb(0., 0., 1.),
+    ];
+
+    for color in colors {
+        let (lightness, chroma, hue) = to_oklch(color);
+
+        assert_close(from_oklch(lightness, chroma, hue, color.a), color);
+    }
+}
+
+#[test]
+fn out_of_gamut_colors_are_clamped() {
+    let color = from_oklch(0.7, 1., 0.5, 1.);
+
+    for channel in [color.r, color.g, color.b] {
+        assert!((0. ..=1.).contains(&channel), "{:?} is out of gamut", color);
+    }
+}
+
+#[test]
+fn gradient_ends_on_its_stops() {
+    let stops = [hex("440154"), hex("21918c"), hex("fde725")];
+
+    for count in [2, 3, NUM_PERCS] {
+        let colors = gradient(&stops, count);
+
+        assert_eq!(colors.len(), count);
+        assert_close(colors[0], stops[0]);
+        assert_close(colors[count - 1], stops[2]);
+    }
+}
+
+#[test]
+fn gradient_goes_through_its_stops() {
+    let stops = [hex("440154"), hex("21918c"), hex("fde725")];
+    let colors = gradient(&stops, 5);
+
+    assert_close(colors[2], stops[1]);
+}
+
+#[test]
+fn gradient_edge_cases() {
+    assert!(gradient(&[], 4).is_empty());
+    assert!(gradient(&[Color::WHITE], 0).is_empty());
+    assert_eq!(gradient(&[Color::WHITE], 3), vec![Color::WHITE; 3]);
+    assert_eq!(gradient(&[Color::WHITE, Color::BLACK], 1), vec![Color::WHITE]);
+}
+
+#[test]
+fn categorical_presets_are_not_blended() {
+    let stops = Preset::OkabeIto.stops();
+    let colors = Preset::OkabeIto.colors(NUM_PERCS);
+
+    assert_eq!(colors.len(), NUM_PERCS);
+    assert_eq!(colors[..stops.len()], stops[..]);
+
+    // the repeated colors keep their hue, darker
+    for (color, stop) in colors[stops.len()..].iter().zip(&stops) {
+        let (lightness, _, hue) = to_oklch(*color);
+        let (stop_lightness, _, stop_hue) = to_oklch(*stop);
+
+        assert!(lightness < stop_lightness);
+        assert!((hue - stop_hue).abs() < 0.05);
+    }
+}
+
+#[test]
+fn preset_grid_colors() {
+    match GridColor::preset(Preset::Viridis) {
+        GridColor::Multitrack(colors) => {
+            let stops = Preset::Viridis.stops();
+
+            assert_close(colors[0], stops[0]);
+            assert_close(colors[NUM_PERCS - 1], stops[stops.len() - 1]);
+        }
+        GridColor::Simple(_) => panic!("expected one color per track"),
+    }
+}
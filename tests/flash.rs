@@ -0,0 +1,66 @@
+//! How the played events flash, with `style::grid::Flash`.
+
+use std::time::Duration;
+
+use iced_native::Color;
+
+use iced_sequencing::style::grid::Flash;
+
+const EPSILON: f32 = 0.001;
+
+fn get_flash(duration_ms: u64) -> Flash {
+    Flash {
+        color: Color::WHITE,
+        duration: Duration::from_millis(duration_ms),
+        spread: 3.,
+    }
+}
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < EPSILON, "{} != {}", a, b);
+}
+
+#[test]
+fn intensity_decays_to_zero() {
+    let flash = get_flash(300);
+
+    assert_close(flash.intensity(Duration::ZERO, 1.), 1.);
+    // quadratic, a quarter left halfway
+    assert_close(flash.intensity(Duration::from_millis(150), 1.), 0.25);
+    assert_close(flash.intensity(Duration::from_millis(300), 1.), 0.);
+    assert_close(flash.intensity(Duration::from_secs(1), 1.), 0.);
+
+    let intensities: Vec<f32> = (0..=30)
+        .map(|ms| flash.intensity(Duration::from_millis(ms * 10), 1.))
+        .collect();
+
+    assert!(
+        intensities.windows(2).all(|pair| pair[1] < pair[0] || pair[1] == 0.),
+        "not decreasing: {:?}",
+        intensities
+    );
+}
+
+#[test]
+fn intensity_scales_with_velocity() {
+    let flash = get_flash(300);
+
+    for ms in [0, 50, 100, 200, 299] {
+        let elapsed = Duration::from_millis(ms);
+        let full = flash.intensity(elapsed, 1.);
+
+        assert_close(flash.intensity(elapsed, 0.5), full * 0.5);
+        assert_close(flash.intensity(elapsed, 0.), 0.);
+    }
+}
+
+#[test]
+fn zero_duration_never_flashes() {
+    let flash = get_flash(0);
+
+    let intensity = flash.intensity(Duration::ZERO, 1.);
+
+    assert!(!intensity.is_nan());
+    assert_close(intensity, 0.);
+    assert_close(flash.intensity(Duration::from_millis(10), 1.), 0.);
+}
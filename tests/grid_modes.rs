@@ -0,0 +1,210 @@
+//! State machine tests for the grid, driven by raw iced events.
+//!
+//! Every message the grid emits is applied with `manage_state_update`, like a
+//! host application would, before the next event is sent.
+
+use iced_native::keyboard::{self, KeyCode, Modifiers};
+use iced_native::{clipboard, layout, mouse, Event, Layout, Length, Point, Shell, Size, Widget};
+
+use ganic_no_std::NUM_PERCS;
+use iced_sequencing::core::grid::{
+    get_event_bounds, manage_state_update, GridEvent, GridMessage, GridPattern, Viewport,
+};
+use iced_sequencing::graphics::headless;
+use iced_sequencing::native::grid::{self, Grid};
+
+const SIZE: Size = Size::new(900., 300.);
+
+struct Host {
+    state: grid::State,
+    live_pattern: GridPattern,
+    focused_track: usize,
+    cursor: Point,
+}
+
+impl Host {
+    fn new(pattern: GridPattern) -> Self {
+        let mut state = grid::State::new(pattern.clone());
+        state.focus();
+
+        Host {
+            state,
+            live_pattern: pattern,
+            focused_track: 0,
+            cursor: Point::ORIGIN,
+        }
+    }
+
+    fn send(&mut self, event: Event) {
+        let renderer = headless::renderer();
+        let mut grid_messages = vec![];
+
+        {
+            let mut grid: Grid<GridMessage, headless::Renderer> = Grid::new(
+                &mut self.state,
+                self.live_pattern.clone(),
+                |message| message,
+                Length::Fill,
+                Length::Fill,
+            );
+            let node = grid.layout(&renderer, &layout::Limits::new(Size::ZERO, SIZE));
+
+            grid.on_event(
+                event,
+                Layout::new(&node),
+                self.cursor,
+                &renderer,
+                &mut clipboard::Null,
+                &mut Shell::new(&mut grid_messages),
+            );
+        }
+
+        for message in grid_messages {
+            manage_state_update(
+                message,
+                &mut self.state,
+                &mut self.live_pattern,
+                &mut self.focused_track,
+            );
+        }
+    }
+
+    fn press(&mut self, position: Point) {
+        self.move_to(position);
+        self.send(Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)));
+    }
+
+    fn move_to(&mut self, position: Point) {
+        self.cursor = position;
+        self.send(Event::Mouse(mouse::Event::CursorMoved { position }));
+    }
+
+    fn modifiers(&mut self, modifiers: Modifiers) {
+        self.send(Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)));
+    }
+
+    fn escape(&mut self, modifiers: Modifiers) {
+        self.send(Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code: KeyCode::Escape,
+            modifiers,
+        }));
+    }
+}
+
+// velocities away from 0.5 keep the center of an event off its velocity handle
+fn get_event(velocity: f32, selected: bool) -> GridEvent {
+    GridEvent {
+        offset: 0.,
+        velocity,
+        selected,
+    }
+}
+
+// also the center of an empty step when no event is there
+fn get_center(step: usize, track: usize) -> Point {
+    get_event_bounds(step, track, 0., SIZE, Viewport::default()).center()
+}
+
+// a comparable description of a pattern
+fn summarize(pattern: &GridPattern) -> Vec<((usize, usize), i32, i32, bool)> {
+    let mut events = pattern
+        .data
+        .iter()
+        .map(|(grid_id, event)| {
+            (
+                *grid_id,
+                (event.offset * 1000.).round() as i32,
+                (event.velocity * 1000.).round() as i32,
+                event.selected,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    events.sort_by_key(|(grid_id, ..)| *grid_id);
+    events
+}
+
+fn get_pattern() -> GridPattern {
+    let mut pattern = GridPattern::new();
+    pattern.data.insert((2, 3), get_event(0.75, true));
+    pattern.data.insert((5, 3), get_event(0.25, true));
+    pattern.data.insert((7, NUM_PERCS - 2), get_event(0.75, false));
+    pattern
+}
+
+fn assert_restored(host: &Host, pattern: &GridPattern) {
+    assert_eq!(summarize(&host.live_pattern), summarize(pattern));
+    assert_eq!(summarize(&host.state.clone_base_pattern()), summarize(pattern));
+    assert_eq!(host.state.get_movement(), None);
+}
+
+#[test]
+fn escape_cancels_quantized_move() {
+    let pattern = get_pattern();
+    let mut host = Host::new(pattern.clone());
+
+    host.press(get_center(2, 3));
+    host.move_to(get_center(4, 4));
+    assert_ne!(summarize(&host.live_pattern), summarize(&pattern));
+
+    host.escape(Modifiers::empty());
+    assert_restored(&host, &pattern);
+}
+
+#[test]
+fn escape_cancels_unquantized_move() {
+    let pattern = get_pattern();
+    let mut host = Host::new(pattern.clone());
+
+    host.press(get_center(2, 3));
+    host.modifiers(Modifiers::CTRL);
+    let center = get_center(2, 3);
+    host.move_to(Point::new(center.x + 13., center.y));
+    assert_ne!(summarize(&host.live_pattern), summarize(&pattern));
+
+    host.escape(Modifiers::CTRL);
+    assert_restored(&host, &pattern);
+}
+
+#[test]
+fn escape_cancels_set_velocity() {
+    let pattern = get_pattern();
+    let mut host = Host::new(pattern.clone());
+
+    host.modifiers(Modifiers::CTRL);
+    host.press(get_center(5, 3));
+    let center = get_center(5, 3);
+    host.move_to(Point::new(center.x, center.y - 20.));
+    assert_ne!(summarize(&host.live_pattern), summarize(&pattern));
+
+    host.escape(Modifiers::CTRL);
+    assert_restored(&host, &pattern);
+}
+
+#[test]
+fn escape_cancels_selecting() {
+    let pattern = get_pattern();
+    let mut host = Host::new(pattern.clone());
+
+    // from an empty step, across the unselected event
+    host.press(get_center(4, NUM_PERCS - 3));
+    host.move_to(get_center(8, NUM_PERCS - 1));
+    assert_ne!(summarize(&host.live_pattern), summarize(&pattern));
+
+    host.escape(Modifiers::empty());
+    assert_restored(&host, &pattern);
+}
+
+#[test]
+fn escape_cancels_selecting_with_modifiers() {
+    let pattern = get_pattern();
+    let mut host = Host::new(pattern.clone());
+
+    host.modifiers(Modifiers::SHIFT);
+    host.press(get_center(4, NUM_PERCS - 3));
+    host.move_to(get_center(8, NUM_PERCS - 1));
+    assert_ne!(summarize(&host.live_pattern), summarize(&pattern));
+
+    host.escape(Modifiers::SHIFT | Modifiers::ALT);
+    assert_restored(&host, &pattern);
+}
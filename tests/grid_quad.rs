@@ -0,0 +1,215 @@
+//! Draws the grid with `grid::quad` on a renderer recording its quads and
+//! text, as a custom `iced_native` renderer would draw it.
+
+use iced_native::{renderer, text, Background, Color, Font, Point, Rectangle, Size, Vector};
+
+use ganic_no_std::NUM_PERCS;
+use iced_sequencing::core::grid::{
+    get_event_content_bounds, get_step_dimensions, GridEvent, GridPattern, Snap, Viewport,
+};
+use iced_sequencing::native::grid::{geometry, quad, Status};
+use iced_sequencing::style::grid::{GridColor, MyDefault, Style, StyleSheet, VelocityDisplay};
+
+const BOUNDS: Rectangle = Rectangle { x: 10., y: 20., width: 900., height: 300. };
+
+#[derive(Debug, Default)]
+struct Recorder {
+    translation: Vector,
+    quads: Vec<(Rectangle, Color)>,
+    texts: Vec<(String, Rectangle, Color)>,
+}
+
+impl iced_native::Renderer for Recorder {
+    fn with_layer(&mut self, _bounds: Rectangle, f: impl FnOnce(&mut Self)) {
+        f(self)
+    }
+
+    fn with_translation(&mut self, translation: Vector, f: impl FnOnce(&mut Self)) {
+        let previous = self.translation;
+        self.translation = previous + translation;
+
+        f(self);
+
+        self.translation = previous;
+    }
+
+    fn clear(&mut self) {
+        self.quads.clear();
+        self.texts.clear();
+    }
+
+    fn fill_quad(&mut self, quad: renderer::Quad, background: impl Into<Background>) {
+        let Background::Color(color) = background.into();
+
+        self.quads.push((quad.bounds + self.translation, color));
+    }
+}
+
+impl text::Renderer for Recorder {
+    type Font = Font;
+
+    const ICON_FONT: Font = Font::Default;
+    const CHECKMARK_ICON: char = '✓';
+    const ARROW_DOWN_ICON: char = '▼';
+
+    fn default_size(&self) -> u16 {
+        20
+    }
+
+    fn measure(&self, content: &str, size: u16, _font: Font, _bounds: Size) -> (f32, f32) {
+        (content.len() as f32 * f32::from(size) / 2., f32::from(size))
+    }
+
+    fn hit_test(
+        &self,
+        _contents: &str,
+        _size: f32,
+        _font: Font,
+        _bounds: Size,
+        _point: Point,
+        _nearest_only: bool,
+    ) -> Option<text::Hit> {
+        None
+    }
+
+    fn fill_text(&mut self, text: text::Text<'_, Font>) {
+        self.texts.push((
+            text.content.to_string(),
+            text.bounds + self.translation,
+            text.color,
+        ));
+    }
+}
+
+struct LabelStyle;
+
+impl StyleSheet for LabelStyle {
+    fn default(&self) -> Style {
+        let mut style = MyDefault.default();
+        style.event.velocity_display = VelocityDisplay::Label { color: Color::WHITE, size: 10. };
+
+        style
+    }
+}
+
+fn get_event(velocity: f32) -> GridEvent {
+    GridEvent {
+        offset: 0.,
+        velocity,
+        selected: false,
+    }
+}
+
+fn draw(pattern: &GridPattern, style_sheet: &dyn StyleSheet) -> Recorder {
+    let mut recorder = Recorder::default();
+
+    quad::draw(
+        &mut recorder,
+        BOUNDS,
+        BOUNDS,
+        Point::new(-1., -1.),
+        pattern,
+        None,
+        Viewport::default(),
+        Snap::default(),
+        Status::Active,
+        None,
+        None,
+        false,
+        None,
+        false,
+        [0; NUM_PERCS],
+        None,
+        [None; NUM_PERCS],
+        [false; NUM_PERCS],
+        false,
+        style_sheet,
+    );
+
+    recorder
+}
+
+// in the window, the content being drawn from the top left of the bounds
+fn get_window_bounds(bounds: Rectangle) -> Rectangle {
+    bounds + Vector::new(BOUNDS.x, BOUNDS.y)
+}
+
+#[test]
+fn events_are_drawn_at_their_bounds() {
+    let style = MyDefault.default();
+    let mut pattern = GridPattern::new();
+    pattern.data.insert((4, 2), get_event(0.75));
+
+    let recorder = draw(&pattern, &MyDefault);
+
+    let content_size = Viewport::default().get_content_size(BOUNDS.size());
+    let event_bounds = get_event_content_bounds(4, 2, 0., content_size);
+    let bounds = get_window_bounds(Rectangle::new(
+        event_bounds.position(),
+        get_step_dimensions(content_size),
+    ));
+    let color = match style.event.bg_color {
+        GridColor::Simple(color) => color,
+        GridColor::Multitrack(colors) => colors[2],
+    };
+
+    assert!(
+        recorder.quads.contains(&(bounds, color)),
+        "no event quad at {:?} in {:?}",
+        bounds,
+        recorder.quads
+    );
+}
+
+#[test]
+fn every_event_fill_is_drawn() {
+    let style = MyDefault.default();
+    let mut pattern = GridPattern::new();
+    pattern.data.insert((0, 0), get_event(1.));
+    pattern.data.insert((7, 3), get_event(0.5));
+    pattern.data.insert((15, 9), get_event(0.25));
+
+    let recorder = draw(&pattern, &MyDefault);
+
+    let content_size = Viewport::default().get_content_size(BOUNDS.size());
+    let shapes = geometry::steps(
+        content_size,
+        &pattern,
+        None,
+        get_step_dimensions(content_size),
+        None,
+        [false; NUM_PERCS],
+        &style,
+    );
+
+    for shape in shapes {
+        if let geometry::Shape::Fill(bounds, color) = shape {
+            let quad = (get_window_bounds(bounds), color);
+
+            assert!(recorder.quads.contains(&quad), "{:?} was not drawn", quad);
+        }
+    }
+}
+
+#[test]
+fn label_velocity_display_draws_the_velocity() {
+    let mut pattern = GridPattern::new();
+    pattern.data.insert((4, 2), get_event(0.75));
+
+    let recorder = draw(&pattern, &LabelStyle);
+
+    let content_size = Viewport::default().get_content_size(BOUNDS.size());
+    let event_bounds = get_window_bounds(Rectangle::new(
+        get_event_content_bounds(4, 2, 0., content_size).position(),
+        get_step_dimensions(content_size),
+    ));
+
+    // 0.75 of 127
+    let (_, bounds, _) = recorder
+        .texts
+        .iter()
+        .find(|(content, _, _)| content == "95")
+        .expect("no velocity label");
+
+    assert_eq!(bounds.position(), event_bounds.center());
+}
@@ -0,0 +1,156 @@
+//! Headless rendering tests for the grid, runnable without a GPU.
+//!
+//! Each drawing is described as text by `graphics::headless::describe` and
+//! compared to `tests/snapshots/<name>.txt`. Set `UPDATE_SNAPSHOTS=1` to write
+//! missing snapshots, or rewrite them after an intended change of the renderer.
+
+use std::{env, fs, path::PathBuf};
+
+use iced_native::{Color, Length, Point, Size};
+
+use ganic_no_std::{NUM_PERCS, NUM_STEPS};
+use iced_sequencing::core::grid::{GridEvent, GridPattern};
+use iced_sequencing::graphics::headless::{self, describe, format_color};
+use iced_sequencing::native::grid::{self, Grid};
+use iced_sequencing::style::grid::{GridColor, MyDefault, StyleSheet};
+
+const SIZE: Size = Size::new(900., 300.);
+
+fn get_event(offset: f32, selected: bool) -> GridEvent {
+    GridEvent {
+        offset,
+        velocity: 0.75,
+        selected,
+    }
+}
+
+fn render(state: &mut grid::State, pattern: &GridPattern) -> String {
+    let grid: Grid<(), headless::Renderer> =
+        Grid::new(state, pattern.clone(), |_| (), Length::Fill, Length::Fill);
+
+    describe(&headless::draw(&grid, SIZE, Point::new(-1., -1.)))
+}
+
+fn assert_snapshot(name: &str, description: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", name]
+        .iter()
+        .collect::<PathBuf>()
+        .with_extension("txt");
+
+    if env::var("UPDATE_SNAPSHOTS").is_ok() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, description).unwrap();
+
+        return;
+    }
+
+    match fs::read_to_string(&path) {
+        Ok(snapshot) => assert_eq!(snapshot, description, "{} changed", path.display()),
+        Err(_) => panic!(
+            "no snapshot at {}, run the tests with UPDATE_SNAPSHOTS=1 to write it",
+            path.display()
+        ),
+    }
+}
+
+fn assert_has_mesh_color(description: &str, color: Color) {
+    let color = format!("mesh {}", format_color(color));
+
+    assert!(description.contains(&color), "no {} in\n{}", color, description);
+}
+
+fn get_track_color(grid_color: GridColor, track: usize) -> Color {
+    match grid_color {
+        GridColor::Simple(color) => color,
+        GridColor::Multitrack(colors) => colors[track],
+    }
+}
+
+#[test]
+fn selection() {
+    let style = MyDefault.default();
+    let mut pattern = GridPattern::new();
+    pattern.data.insert((0, 0), get_event(0., false));
+    pattern.data.insert((4, 2), get_event(0., true));
+
+    let mut state = grid::State::new(pattern.clone());
+    let description = render(&mut state, &pattern);
+
+    assert_has_mesh_color(&description, style.event.contour_bg_color);
+    assert_snapshot("selection", &description);
+}
+
+#[test]
+fn mutes() {
+    let style = MyDefault.default();
+    let mut pattern = GridPattern::new();
+    pattern.data.insert((0, NUM_PERCS - 1), get_event(0., false));
+
+    let mut state = grid::State::new(pattern.clone());
+    // pattern index 0 is the last grid track
+    state.set_mute(0, true);
+    let description = render(&mut state, &pattern);
+
+    let muted_color = Color {
+        a: 0.05,
+        ..get_track_color(style.event.bg_color, NUM_PERCS - 1)
+    };
+
+    assert_has_mesh_color(&description, muted_color);
+    assert_snapshot("mutes", &description);
+}
+
+#[test]
+fn offsets() {
+    let style = MyDefault.default();
+    let mut pattern = GridPattern::new();
+    pattern.data.insert((2, 1), get_event(0.25, false));
+    pattern.data.insert((6, 1), get_event(-0.25, false));
+
+    let mut state = grid::State::new(pattern.clone());
+    let description = render(&mut state, &pattern);
+
+    assert_has_mesh_color(&description, style.event.positive_offset_marker_bg_color);
+    assert_has_mesh_color(&description, style.event.negative_offset_marker_bg_color);
+    assert_snapshot("offsets", &description);
+}
+
+#[test]
+fn playback_highlight() {
+    let style = MyDefault.default();
+    let mut pattern = GridPattern::new();
+    pattern.data.insert((3, NUM_PERCS - 1), get_event(0., false));
+
+    let mut state = grid::State::new(pattern.clone());
+    state.is_playing(true);
+    state.transport([Some(3); NUM_PERCS]);
+    state.set_playhead(Some(3.5));
+    let description = render(&mut state, &pattern);
+
+    assert_has_mesh_color(&description, style.current_step_bg_color);
+    assert_has_mesh_color(
+        &description,
+        get_track_color(style.event.slider_highlighted_bg_color, NUM_PERCS - 1),
+    );
+    assert_snapshot("playback_highlight", &description);
+}
+
+#[test]
+fn multitrack_colors() {
+    let style = MyDefault.default();
+    let mut pattern = GridPattern::new();
+
+    for track in 0..NUM_PERCS {
+        pattern.data.insert((track % NUM_STEPS, track), get_event(0., false));
+    }
+
+    let mut state = grid::State::new(pattern.clone());
+    let description = render(&mut state, &pattern);
+
+    for track in 0..NUM_PERCS {
+        assert_has_mesh_color(&description, get_track_color(style.event.bg_color, track));
+        assert_has_mesh_color(&description, get_track_color(style.event.slider_bg_color, track));
+    }
+
+    assert_snapshot("multitrack_colors", &description);
+}
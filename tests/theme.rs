@@ -0,0 +1,194 @@
+//! Parsing of the TOML theme files, run with `--features theme`.
+
+use std::convert::TryFrom;
+
+use iced_native::Color;
+
+use ganic_no_std::NUM_PERCS;
+use iced_sequencing::style::grid::{self, GridColor, MyDefault, StyleSheet, VelocityDisplay};
+use iced_sequencing::style::multi_slider::{self, StyleSheet as _};
+use iced_sequencing::style::theme::{GridColorOverride, HexColor, Theme, ThemeError};
+
+fn hex_color(value: &str) -> Result<Color, String> {
+    HexColor::try_from(String::from(value)).map(Color::from)
+}
+
+fn track_colors(count: usize) -> String {
+    let colors = vec!["\"#ff7e53\""; count].join(", ");
+    format!("[grid.event]\nbg_color = [{}]", colors)
+}
+
+#[test]
+fn hex_color_without_alpha() {
+    assert_eq!(hex_color("#ff7e53"), Ok(Color::from_rgb8(0xff, 0x7e, 0x53)));
+    assert_eq!(hex_color("#FF7E53"), Ok(Color::from_rgb8(0xff, 0x7e, 0x53)));
+}
+
+#[test]
+fn hex_color_with_alpha() {
+    assert_eq!(
+        hex_color("#35434580"),
+        Ok(Color::from_rgba8(0x35, 0x43, 0x45, 128. / 255.))
+    );
+    assert_eq!(hex_color("#00000000").map(|color| color.a), Ok(0.));
+    assert_eq!(hex_color("#000000ff").map(|color| color.a), Ok(1.));
+}
+
+#[test]
+fn invalid_hex_colors() {
+    for value in ["", "#", "#fff", "#ff7e5", "#ff7e53a", "#ff7e53800", "#gg7e53", "#ff7e53zz"] {
+        assert!(hex_color(value).is_err(), "{:?} was accepted", value);
+    }
+}
+
+#[test]
+fn one_color_per_track() {
+    let theme = Theme::from_toml(&track_colors(NUM_PERCS)).unwrap();
+
+    match theme.grid.event.bg_color {
+        Some(GridColorOverride(GridColor::Multitrack(colors))) => {
+            assert!(colors.iter().all(|color| *color == Color::from_rgb8(0xff, 0x7e, 0x53)));
+        }
+        color => panic!("expected one color per track, got {:?}", color),
+    }
+}
+
+#[test]
+fn wrong_number_of_track_colors() {
+    for count in [0, 1, NUM_PERCS - 1, NUM_PERCS + 1] {
+        assert!(
+            matches!(Theme::from_toml(&track_colors(count)), Err(ThemeError::Parse(_))),
+            "{} track colors were accepted",
+            count
+        );
+    }
+}
+
+#[test]
+fn unknown_fields_are_denied() {
+    let sources = [
+        "unknown = 1",
+        "[grid]\nunknown = 1",
+        "[grid.event]\nunknown = 1",
+        "[grid.event]\nflash = { unknown = 1 }",
+        "[grid.hovered]\nunknown = 1",
+        "[grid]\nplayhead = { color = \"#fafafa\", width = 2.0 }",
+        "[multi_slider.slider]\nunknown = 1",
+        "[snapshot]\nunknown = 1",
+        "[h_list]\nunknown = 1",
+    ];
+
+    for source in sources {
+        assert!(
+            matches!(Theme::from_toml(source), Err(ThemeError::Parse(_))),
+            "{:?} was accepted",
+            source
+        );
+    }
+}
+
+#[test]
+fn nested_grid_states_are_denied() {
+    let source = "[grid.hovered.focused.grid]\nedge_step_bg_color = \"#252f30\"";
+
+    assert!(matches!(Theme::from_toml(source), Err(ThemeError::Parse(_))));
+}
+
+#[test]
+fn empty_theme_keeps_the_default_style() {
+    let theme = Theme::from_toml("").unwrap();
+    let style = theme.grid.default();
+
+    assert_eq!(style.current_step_bg_color, MyDefault.default().current_step_bg_color);
+    assert_eq!(
+        theme.grid.hovered().grid.edge_step_bg_color,
+        MyDefault.hovered().grid.edge_step_bg_color
+    );
+}
+
+#[test]
+fn grid_states_apply_over_the_grid_section() {
+    let source = r##"
+        [grid]
+        current_step_bg_color = "#ffffff"
+
+        [grid.tooltip]
+        text_size = 20.0
+
+        [grid.hovered]
+        current_step_bg_color = "#000000"
+    "##;
+    let theme = Theme::from_toml(source).unwrap();
+
+    assert_eq!(theme.grid.default().current_step_bg_color, Color::WHITE);
+    assert_eq!(theme.grid.focused().current_step_bg_color, Color::WHITE);
+    assert_eq!(theme.grid.hovered().current_step_bg_color, Color::BLACK);
+    assert_eq!(theme.grid.hovered().tooltip.text_size, 20.);
+}
+
+#[test]
+fn event_velocity_display_and_flash() {
+    let source = r##"
+        [grid.event]
+        velocity_display = { gradient = ["#000000", "#ffffff"] }
+        flash = { duration_ms = 120 }
+
+        [grid.disabled.event]
+        velocity_display = "height"
+        flash = { enabled = false }
+    "##;
+    let theme = Theme::from_toml(source).unwrap();
+
+    let event = theme.grid.default().event;
+    assert!(matches!(
+        event.velocity_display,
+        VelocityDisplay::Gradient(low, high) if low == Color::BLACK && high == Color::WHITE
+    ));
+    assert_eq!(event.flash.map(|flash| flash.duration.as_millis()), Some(120));
+
+    let event = theme.grid.disabled().event;
+    assert!(matches!(event.velocity_display, VelocityDisplay::Height));
+    assert!(event.flash.is_none());
+}
+
+#[test]
+fn context_menu_and_readout() {
+    let source = r##"
+        [grid.context_menu]
+        hovered_text_color = "#000000"
+        background = { border_width = 3.0 }
+
+        [grid.readout]
+        text_color = "#ffffff"
+    "##;
+    let style: grid::Style = Theme::from_toml(source).unwrap().grid.default();
+
+    assert_eq!(style.context_menu.hovered_text_color, Color::BLACK);
+    assert_eq!(style.context_menu.background.border_width, 3.);
+    assert_eq!(
+        style.context_menu.text_color,
+        MyDefault.default().context_menu.text_color
+    );
+    assert_eq!(style.readout.text_color, Color::WHITE);
+}
+
+#[test]
+fn multi_slider_colors() {
+    let source = r##"
+        [multi_slider.slider]
+        color = "#ffffff"
+
+        [multi_slider.hovered]
+        color = "#000000"
+    "##;
+    let theme = Theme::from_toml(source).unwrap().multi_slider;
+    let primary_color = Color::from_rgb8(0xff, 0x7e, 0x53);
+
+    assert_eq!(theme.default(primary_color).slider.color, Color::WHITE);
+    assert_eq!(theme.highlight(primary_color).color, Color::WHITE);
+    assert_eq!(theme.hovered(primary_color).color, Color::BLACK);
+    assert_eq!(
+        theme.hovered(primary_color).marker_color,
+        multi_slider::Default.hovered(primary_color).marker_color
+    );
+}